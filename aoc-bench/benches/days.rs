@@ -0,0 +1,60 @@
+use std::{fs, time::Duration};
+
+use aoc_bench::scale;
+use aoc_common::Solution;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
+
+/// How much larger than `input.txt` the scaled inputs are.
+const FACTOR: usize = 4;
+
+fn bench_day<S: Solution>(c: &mut Criterion) {
+    let path = format!("{}/../day{}/input.txt", env!("CARGO_MANIFEST_DIR"), S::DAY);
+    let Ok(input) = fs::read_to_string(&path) else {
+        eprintln!("skipping day {}: cannot read {path}", S::DAY);
+        return;
+    };
+    let scaled = scale(S::DAY, &input, FACTOR).expect("every day can be scaled");
+
+    let mut group = c.benchmark_group(format!("day{}", S::DAY));
+    group
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(2))
+        .sampling_mode(SamplingMode::Flat);
+
+    for (name, text) in [("input".to_string(), &input), (format!("x{FACTOR}"), &scaled)] {
+        let parsed = match S::parse(text) {
+            Ok(parsed) => parsed,
+            Err(err) => panic!("{name}: {err}"),
+        };
+        group.bench_with_input(BenchmarkId::new("parse", &name), text, |b, text| {
+            b.iter(|| S::parse(black_box(text)))
+        });
+        group.bench_with_input(BenchmarkId::new("part1", &name), &parsed, |b, parsed| {
+            b.iter(|| S::part1(black_box(parsed)))
+        });
+        group.bench_with_input(BenchmarkId::new("part2", &name), &parsed, |b, parsed| {
+            b.iter(|| S::part2(black_box(parsed)))
+        });
+    }
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    bench_day::<day1::Day1>(c);
+    bench_day::<day2::Day2>(c);
+    bench_day::<day3::Day3>(c);
+    bench_day::<day4::Day4>(c);
+    bench_day::<day5::Day5>(c);
+    bench_day::<day6::Day6>(c);
+    bench_day::<day7::Day7>(c);
+    bench_day::<day8::Day8>(c);
+    bench_day::<day9::Day9>(c);
+    bench_day::<day10::Day10>(c);
+    bench_day::<day11::Day11>(c);
+    bench_day::<day12::Day12>(c);
+    bench_day::<day13::Day13>(c);
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
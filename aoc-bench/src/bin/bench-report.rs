@@ -0,0 +1,104 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::Parser;
+use serde_json::Value;
+
+/// Summarise the latest `cargo bench -p aoc-bench` run as plain text, one
+/// line per day, stage and input, so two commits' reports can be diffed.
+#[derive(Parser)]
+#[command(name = "bench-report")]
+struct Cli {
+    /// Criterion's output directory
+    #[arg(long, default_value = "target/criterion")]
+    criterion: PathBuf,
+    /// An earlier report to compare against
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+}
+
+/// Day number, stage (`parse`, `part1`, `part2`) and input name.
+type Key = (u8, String, String);
+
+fn read_json(path: &Path) -> io::Result<Value> {
+    let text = fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Finds every `new/benchmark.json` below `dir` and reads the mean time of
+/// the benchmark it describes.
+fn collect(dir: &Path, means: &mut BTreeMap<Key, f64>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if path.ends_with("new") {
+            let benchmark = read_json(&path.join("benchmark.json"))?;
+            let estimates = read_json(&path.join("estimates.json"))?;
+            let day = benchmark["group_id"]
+                .as_str()
+                .and_then(|group| group.strip_prefix("day"))
+                .and_then(|day| day.parse().ok());
+            let stage = benchmark["function_id"].as_str();
+            let input = benchmark["value_str"].as_str();
+            let mean = estimates["mean"]["point_estimate"].as_f64();
+            if let (Some(day), Some(stage), Some(input), Some(mean)) = (day, stage, input, mean) {
+                means.insert((day, stage.to_string(), input.to_string()), mean);
+            }
+        } else {
+            collect(&path, means)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_report(path: &Path) -> io::Result<BTreeMap<Key, f64>> {
+    let text = fs::read_to_string(path)?;
+    let means = text
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [day, stage, input, mean, ..] => Some((
+                (day.strip_prefix("day")?.parse().ok()?, stage.to_string(), input.to_string()),
+                mean.parse().ok()?,
+            )),
+            _ => None,
+        })
+        .collect();
+    Ok(means)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let mut means = BTreeMap::new();
+    if let Err(err) = collect(&cli.criterion, &mut means) {
+        eprintln!("cannot read {}: {err}", cli.criterion.display());
+        return ExitCode::FAILURE;
+    }
+    let baseline = match cli.baseline.as_deref().map(read_report).transpose() {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            eprintln!("cannot read the baseline report: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("# day    stage  input       mean (ns)");
+    for (key, mean) in &means {
+        let (day, stage, input) = key;
+        let day = format!("day{day}");
+        let change = baseline
+            .as_ref()
+            .and_then(|baseline| baseline.get(key))
+            .map(|old| format!("  {:+.1}%", (mean / old - 1.0) * 100.0))
+            .unwrap_or_default();
+        println!("{day:<6}  {stage:<5}  {input:<5}  {mean:>14.0}{change}");
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,106 @@
+/// Builds a larger puzzle input for `day` out of a real one, roughly
+/// `factor` times the work, while keeping it valid for that day's parser.
+///
+/// Returns `None` for a day with no solver.
+pub fn scale(day: u8, input: &str, factor: usize) -> Option<String> {
+    let input = input.trim_end();
+    let scaled = match day {
+        1 | 13 => vec![input; factor].join("\n\n"),
+        2 | 3 | 4 | 7 | 9 | 10 => vec![input; factor].join("\n"),
+        5 => replay_moves(input, factor),
+        6 => input.repeat(factor),
+        8 => tile(input, factor, |_, c| c),
+        11 => repeat_items(input, factor),
+        12 => tile(input, factor, |copy, c| match (copy, c) {
+            (1.., 'S') => 'a',
+            (1.., 'E') => 'z',
+            _ => c,
+        }),
+        _ => return None,
+    };
+    Some(scaled + "\n")
+}
+
+/// Repeats every row of a map `factor` times side by side, letting `cell`
+/// rewrite the characters of each copy.
+fn tile(input: &str, factor: usize, cell: impl Fn(usize, char) -> char) -> String {
+    input
+        .lines()
+        .map(|row| {
+            (0..factor)
+                .flat_map(|copy| row.chars().map(move |c| (copy, c)))
+                .map(|(copy, c)| cell(copy, c))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Plays the moves forwards, then backwards with `from` and `to` swapped,
+/// and so on, so every move stays legal and an odd `factor` ends on the
+/// original answer.
+fn replay_moves(input: &str, factor: usize) -> String {
+    let Some((drawing, moves)) = input.split_once("\n\n") else {
+        return input.to_string();
+    };
+    let forward: Vec<&str> = moves.lines().collect();
+    let backward: Vec<String> = forward
+        .iter()
+        .rev()
+        .map(|line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["move", count, "from", from, "to", to] => format!("move {count} from {to} to {from}"),
+            _ => line.to_string(),
+        })
+        .collect();
+
+    let mut lines = vec![drawing.to_string(), String::new()];
+    for pass in 0..factor {
+        match pass % 2 {
+            0 => lines.extend(forward.iter().map(|line| line.to_string())),
+            _ => lines.extend(backward.iter().cloned()),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Gives every monkey `factor` times its starting items.
+fn repeat_items(input: &str, factor: usize) -> String {
+    input
+        .lines()
+        .map(|line| match line.split_once("Starting items: ") {
+            Some((prefix, items)) => format!("{prefix}Starting items: {}", vec![items; factor].join(", ")),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn input(day: u8) -> String {
+        fs::read_to_string(format!("{}/../day{day}/input.txt", env!("CARGO_MANIFEST_DIR"))).unwrap()
+    }
+
+    #[test]
+    fn scaled_inputs_still_solve() {
+        for day in aoc::DAYS {
+            let scaled = scale(day.day, &input(day.day), 3).unwrap();
+            assert!(scaled.len() > input(day.day).len());
+            if let Err(err) = (day.solve)(&scaled, &[1]) {
+                panic!("{err}");
+            }
+        }
+    }
+
+    #[test]
+    fn replayed_moves_end_on_the_same_crates() {
+        let input = input(5);
+        let scaled = scale(5, &input, 5).unwrap();
+        assert_eq!(day5::process_part1(&scaled).unwrap(), day5::process_part1(&input).unwrap());
+        assert_eq!(day5::process_part2(&scaled).unwrap(), day5::process_part2(&input).unwrap());
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::Display;
+
+/// A 1-based line and column (counted in characters) within a puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// The location of the byte `offset` within `input`.
+    pub fn of_offset(input: &str, offset: usize) -> Self {
+        let before = &input[..offset.min(input.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Location {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// An error raised while parsing or solving a puzzle, pointing at the
+/// offending place in the input when there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub day: u8,
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(day: u8, message: impl Into<String>) -> Self {
+        Error {
+            day,
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(mut self, line: usize, column: usize) -> Self {
+        self.location = Some(Location { line, column });
+        self
+    }
+
+    /// Points the error at the start of `fragment`, which must be a slice
+    /// of `input` (such as a line, a token or the unparsed rest).
+    pub fn at_fragment(mut self, input: &str, fragment: &str) -> Self {
+        let offset = (fragment.as_ptr() as usize).saturating_sub(input.as_ptr() as usize);
+        self.location = Some(Location::of_offset(input, offset));
+        self
+    }
+
+    /// Moves the error to `line`, keeping any column already recorded.
+    pub fn on_line(self, line: usize) -> Self {
+        let column = self.location.map_or(1, |location| location.column);
+        self.at(line, column)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(Location { line, column }) => write!(
+                f,
+                "day {}, line {line}, column {column}: {}",
+                self.day, self.message
+            ),
+            None => write!(f, "day {}: {}", self.day, self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// The error followed, when it has a location, by the offending line
+    /// of `input` with a caret under the column:
+    ///
+    /// ```text
+    /// day 5, line 6, column 6: expected `move N from A to B`
+    ///   |
+    /// 6 | move x from 1 to 2
+    ///   |      ^
+    /// ```
+    ///
+    /// `input` must be the text the location was computed on, normally the
+    /// [`normalize`](crate::normalize)d puzzle input.
+    pub fn render(&self, input: &str) -> String {
+        let Some(Location { line, column }) = self.location else {
+            return self.to_string();
+        };
+        let text = input.split('\n').nth(line - 1).unwrap_or_default();
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        // Tabs stay tabs so the caret lines up however they are displayed.
+        let indent: String = text
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let gutter = " ".repeat(line.to_string().len());
+        format!("{self}\n{gutter} |\n{line} | {text}\n{gutter} | {indent}^")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_of_offset() {
+        let input = "abc\ndéf\ng";
+        assert_eq!(Location::of_offset(input, 0), Location { line: 1, column: 1 });
+        assert_eq!(Location::of_offset(input, 5), Location { line: 2, column: 2 });
+        assert_eq!(Location::of_offset(input, 7), Location { line: 2, column: 3 });
+        assert_eq!(Location::of_offset(input, input.len()), Location { line: 3, column: 2 });
+    }
+
+    #[test]
+    fn display_includes_location() {
+        let input = "1-2\n3x4";
+        let err = Error::new(4, "expected `-`").at_fragment(input, &input[5..]);
+        assert_eq!(err.to_string(), "day 4, line 2, column 2: expected `-`");
+        assert_eq!(Error::new(6, "no marker").to_string(), "day 6: no marker");
+    }
+
+    #[test]
+    fn render_points_at_the_column() {
+        let input = "move 1 from 2 to 3\n\tmove x from 1 to 2\n";
+        let err = Error::new(5, "expected `move N from A to B`").at(2, 7);
+        assert_eq!(
+            err.render(input),
+            "day 5, line 2, column 7: expected `move N from A to B`\n  |\n2 | \tmove x from 1 to 2\n  | \t     ^"
+        );
+        assert_eq!(Error::new(6, "no marker").render(input), "day 6: no marker");
+        let past_the_end = Error::new(6, "unexpected end of input").at(3, 1).render(input);
+        assert!(past_the_end.ends_with("3 | \n  | ^"));
+    }
+}
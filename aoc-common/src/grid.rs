@@ -0,0 +1,297 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{Direction, Error, Point};
+
+/// A rectangular, row-major grid of cells addressed by [`Point`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, failing on the first row whose length
+    /// differs from the first one.
+    pub fn from_rows(day: u8, rows: Vec<Vec<T>>) -> Result<Self, Error> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::new(
+                    day,
+                    format!("expected {width} cells in every row, found {}", row.len()),
+                )
+                .at(i + 1, 1));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    /// Builds a `width` by `height` grid, asking `cell` for every position.
+    pub fn from_fn<F>(width: usize, height: usize, mut cell: F) -> Self
+    where
+        F: FnMut(Point) -> T,
+    {
+        let cells = (0..height as i64)
+            .flat_map(|y| (0..width as i64).map(move |x| Point::new(x, y)))
+            .map(&mut cell)
+            .collect();
+        Grid { width, height, cells }
+    }
+
+    /// Parses a character map, one row per line, converting every character
+    /// with `cell` and reporting the first one it rejects.
+    pub fn parse<F>(day: u8, input: &str, mut cell: F) -> Result<Self, Error>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(j, c)| {
+                        cell(c).ok_or_else(|| {
+                            Error::new(day, format!("unexpected character {c:?}"))
+                                .at(i + 1, j + 1)
+                        })
+                    })
+                    .collect::<Result<Vec<T>, Error>>()
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Grid::from_rows(day, rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as i64).contains(&point.x) && (0..self.height as i64).contains(&point.y)
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.y as usize * self.width + point.x as usize)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height as i64)
+            .flat_map(move |y| (0..width as i64).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The first position whose cell matches `predicate`.
+    pub fn position<F>(&self, mut predicate: F) -> Option<Point>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// The orthogonal neighbours of `point` that lie inside the grid.
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours4().filter(|&p| self.contains(p))
+    }
+
+    /// The orthogonal and diagonal neighbours of `point` inside the grid.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours8().filter(|&p| self.contains(p))
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells[x..].iter().step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// The cells met walking from `from` (exclusive) towards `direction`
+    /// until the edge of the grid.
+    pub fn ray(&self, from: Point, direction: Direction) -> impl Iterator<Item = (Point, &T)> {
+        let mut point = from;
+        std::iter::from_fn(move || {
+            point += direction;
+            self.get(point).map(|cell| (point, cell))
+        })
+    }
+
+    pub fn map<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> Grid<T> {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        let cells = (0..self.width)
+            .flat_map(|x| {
+                let mut column: Vec<T> = self.column(x).cloned().collect();
+                column.reverse();
+                column
+            })
+            .collect();
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Rotates the grid a quarter turn counter-clockwise.
+    pub fn rotate_counter_clockwise(&self) -> Grid<T> {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse(0, "abc\ndef", Some).unwrap()
+    }
+
+    fn text(grid: &Grid<char>) -> Vec<String> {
+        grid.rows().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn parse_and_get() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.position(|&c| c == 'e'), Some(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn parse_reports_bad_cells_and_ragged_rows() {
+        let err = Grid::parse(8, "12\n3x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "day 8, line 2, column 2: unexpected character 'x'");
+        let err = Grid::parse(8, "12\n3", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.location.map(|l| l.line), Some(2));
+    }
+
+    #[test]
+    fn rows_columns_and_rays() {
+        let grid = sample();
+        assert_eq!(grid.row(1).collect::<String>(), "def");
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        let ray: String = grid
+            .ray(Point::new(0, 0), Direction::Right)
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(ray, "bc");
+        assert_eq!(grid.ray(Point::new(0, 0), Direction::Up).count(), 0);
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = sample();
+        assert_eq!(grid.neighbours4(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Point::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn transpose_and_rotate() {
+        let grid = sample();
+        assert_eq!(text(&grid.transpose()), ["ad", "be", "cf"]);
+        assert_eq!(text(&grid.rotate_clockwise()), ["da", "eb", "fc"]);
+        assert_eq!(text(&grid.rotate_counter_clockwise()), ["cf", "be", "ad"]);
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use crate::Error;
+
+/// The puzzle input as the parsers expect it: `\n` line endings, no byte
+/// order mark and no blank lines at the end. Everything else, trailing
+/// spaces on a line included, is left untouched.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let text = if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(input)
+    };
+
+    // Keep everything up to the end of the last line with any content.
+    let end = match text.rfind(|c: char| !c.is_whitespace()) {
+        Some(last) => text[last..].find('\n').map_or(text.len(), |i| last + i + 1),
+        None => 0,
+    };
+    match text {
+        Cow::Borrowed(text) => Cow::Borrowed(&text[..end]),
+        Cow::Owned(mut text) => {
+            text.truncate(end);
+            Cow::Owned(text)
+        }
+    }
+}
+
+/// Reads a puzzle input file and [`normalize`]s it.
+pub fn load_input(path: impl AsRef<Path>) -> io::Result<String> {
+    let text = fs::read_to_string(path)?;
+    Ok(normalize(&text).into_owned())
+}
+
+/// Calls `line` with every line of `reader` and its 1-based number, as
+/// they would be after [`normalize`]: without the byte order mark, the
+/// `\r` of `\r\n` endings or the blank lines at the end. Lines are read
+/// one at a time into the same buffer, so the input never has to fit in
+/// memory; only a run of blank lines is held back until a line with
+/// content shows it is not the end.
+///
+/// Errors from `line` are returned as they are, so it should locate them.
+/// Reading stops at the first one, so an input with several problems can
+/// report a different one than a solver that parses it all first.
+pub fn for_each_line<R: BufRead>(
+    day: u8,
+    mut reader: R,
+    mut line: impl FnMut(usize, &str) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut buffer = String::new();
+    let mut blank: Vec<String> = vec![];
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        let read = reader.read_line(&mut buffer).map_err(|err| {
+            Error::new(day, format!("cannot read input: {err}")).at(number + 1, 1)
+        })?;
+        if read == 0 {
+            return Ok(());
+        }
+        number += 1;
+
+        let mut text = match buffer.strip_suffix('\n') {
+            Some(text) => text.strip_suffix('\r').unwrap_or(text),
+            None => &buffer,
+        };
+        if number == 1 {
+            text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        }
+        if text.trim().is_empty() {
+            blank.push(text.to_string());
+            continue;
+        }
+        let first_blank = number - blank.len();
+        for (i, text) in blank.drain(..).enumerate() {
+            line(first_blank + i, &text)?;
+        }
+        line(number, text)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_windows_files() {
+        let text = "\u{feff}1000\r\n2000\r\n\r\n3000\r\n\r\n \r\n";
+        assert_eq!(normalize(text), "1000\n2000\n\n3000\n");
+    }
+
+    #[test]
+    fn keeps_significant_whitespace() {
+        let text = "    [D]    \n 1   2 \n\n\n";
+        assert_eq!(normalize(text), "    [D]    \n 1   2 \n");
+        assert_eq!(normalize("no newline  "), "no newline  ");
+        assert!(matches!(normalize("a\nb\n"), Cow::Borrowed("a\nb\n")));
+        assert_eq!(normalize("\n \n"), "");
+    }
+
+    #[test]
+    fn streams_normalized_lines() {
+        for text in ["\u{feff}1000\r\n2000\r\n\r\n \r\n3000\r\n\r\n \r\n", "a\r", ""] {
+            let mut lines = vec![];
+            for_each_line(1, text.as_bytes(), |number, line| {
+                lines.push((number, line.to_string()));
+                Ok(())
+            })
+            .unwrap();
+            let expected: Vec<_> = normalize(text)
+                .lines()
+                .enumerate()
+                .map(|(i, line)| (i + 1, line.to_string()))
+                .collect();
+            assert_eq!(lines, expected, "{text:?}");
+        }
+    }
+}
@@ -0,0 +1,15 @@
+mod error;
+mod grid;
+mod input;
+mod parser;
+mod point;
+mod solution;
+mod visualize;
+
+pub use error::{Error, Location};
+pub use grid::Grid;
+pub use input::{for_each_line, load_input, normalize};
+pub use parser::{entry, finish, ParseResult};
+pub use point::{Direction, Point};
+pub use solution::Solution;
+pub use visualize::{Frame, Visualize};
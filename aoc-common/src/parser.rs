@@ -0,0 +1,100 @@
+use nom::{
+    combinator::cut,
+    error::{context, VerboseError, VerboseErrorKind},
+    IResult, Parser,
+};
+
+use crate::Error;
+
+/// What every day's nom parsers return: errors keep the contexts they
+/// passed through, so [`finish`] can say what was expected.
+pub type ParseResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// Parses one entry of a list, such as a line or a block of lines, failing
+/// for good with the `expected` message when it does not parse. Lists then
+/// report the broken entry instead of quietly ending before it. Only
+/// whitespace left ends a list as usual.
+pub fn entry<'a, O, F>(expected: &'static str, parser: F) -> impl FnMut(&'a str) -> ParseResult<'a, O>
+where
+    F: Parser<&'a str, O, VerboseError<&'a str>>,
+{
+    let mut parser = cut(context(expected, parser));
+    move |input: &'a str| {
+        if input.trim().is_empty() {
+            return Err(nom::Err::Error(VerboseError {
+                errors: vec![(input, VerboseErrorKind::Context(expected))],
+            }));
+        }
+        parser(input)
+    }
+}
+
+/// Turns the result of a top-level nom parser into the parsed value,
+/// rejecting anything but trailing whitespace left after it.
+///
+/// A failure is reported where the innermost parser gave up, with the
+/// innermost [`context`] as its message.
+pub fn finish<'a, O>(day: u8, input: &'a str, result: ParseResult<'a, O>) -> Result<O, Error> {
+    match result {
+        Ok((rest, output)) if rest.trim().is_empty() => Ok(output),
+        Ok((rest, _)) => {
+            Err(Error::new(day, "unexpected trailing input").at_fragment(input, rest))
+        }
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            let context = err.errors.iter().find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some(context.to_string()),
+                _ => None,
+            });
+            let message = match (context, err.errors.first()) {
+                (Some(context), _) => context,
+                (None, Some((_, VerboseErrorKind::Char(c)))) => format!("expected {c:?}"),
+                (None, Some((_, VerboseErrorKind::Nom(kind)))) => {
+                    format!("failed to parse ({})", kind.description())
+                }
+                _ => "failed to parse".to_string(),
+            };
+            let error = Error::new(day, message);
+            Err(match err.errors.first() {
+                Some((rest, _)) => error.at_fragment(input, rest),
+                None => error,
+            })
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            Err(Error::new(day, "unexpected end of input").at_fragment(input, &input[input.len()..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{newline, u32},
+        multi::separated_list1,
+        sequence::separated_pair,
+    };
+
+    use super::*;
+
+    fn pairs(input: &str) -> ParseResult<'_, Vec<(u32, u32)>> {
+        let pair = separated_pair(u32, context("expected `,`", tag(",")), u32);
+        separated_list1(newline, entry("expected `A,B`", pair))(input)
+    }
+
+    #[test]
+    fn reports_the_broken_entry() {
+        let input = "1,2\n3;4\n5,6\n";
+        let err = finish(0, input, pairs(input)).unwrap_err();
+        assert_eq!(err.to_string(), "day 0, line 2, column 2: expected `,`");
+
+        let input = "1,2\nx\n";
+        let err = finish(0, input, pairs(input)).unwrap_err();
+        assert_eq!(err.to_string(), "day 0, line 2, column 1: expected `A,B`");
+    }
+
+    #[test]
+    fn trailing_whitespace_ends_a_list() {
+        let input = "1,2\n3,4\n\n";
+        assert_eq!(finish(0, input, pairs(input)).unwrap(), [(1, 2), (3, 4)]);
+    }
+}
@@ -0,0 +1,16 @@
+use crate::Error;
+
+/// A puzzle split into a parsing step and two solving steps, so the input
+/// is parsed once and both parts (and their timings) share the result.
+pub trait Solution {
+    const DAY: u8;
+
+    /// The parsed puzzle input, possibly borrowing from the raw text.
+    type Parsed<'a>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error>;
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error>;
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error>;
+}
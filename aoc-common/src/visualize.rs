@@ -0,0 +1,43 @@
+use crate::{Error, Grid, Point, Solution};
+
+/// One step of a simulation, drawn as a grid of characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub cells: Grid<char>,
+}
+
+impl Frame {
+    /// Builds a frame out of lines of text, padding them with spaces to
+    /// `width` and cutting anything beyond it.
+    pub fn from_lines<S: AsRef<str>>(caption: impl Into<String>, width: usize, lines: &[S]) -> Self {
+        let lines: Vec<Vec<char>> = lines.iter().map(|line| line.as_ref().chars().collect()).collect();
+        let cells = Grid::from_fn(width, lines.len(), |Point { x, y }| {
+            lines[y as usize].get(x as usize).copied().unwrap_or(' ')
+        });
+        Frame { caption: caption.into(), cells }
+    }
+}
+
+/// A puzzle whose answer comes out of a step-by-step simulation that can
+/// be watched. Frames are handed over one at a time as the simulation runs,
+/// and every frame of one run has the same size.
+pub trait Visualize: Solution {
+    fn frames(
+        parsed: &Self::Parsed<'_>,
+        part: u8,
+        frame: &mut dyn FnMut(Frame),
+    ) -> Result<(), Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_padded_and_cut() {
+        let frame = Frame::from_lines("step 1", 3, &["ab", "cdef"]);
+        assert_eq!(frame.caption, "step 1");
+        assert_eq!(frame.cells.to_string(), "ab \ncde\n");
+    }
+}
@@ -0,0 +1,17 @@
+//! Calorie lists: `size` elves carrying 1 to 15 snacks each.
+
+use rand::Rng;
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    (0..params.size.max(1))
+        .map(|_| {
+            (0..rng.gen_range(1..=15))
+                .map(|_| rng.gen_range(1000..=60_000).to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -0,0 +1,22 @@
+//! CPU programs: `size` instructions (at least enough for 240 cycles)
+//! keeping the X register on the 40 pixel wide screen.
+
+use rand::Rng;
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let mut x: i32 = 1;
+    (0..params.size.max(240))
+        .map(|_| {
+            if rng.gen_bool(0.3) {
+                return "noop".to_string();
+            }
+            let arg = rng.gen_range(-5..=5);
+            let arg = if (0..40).contains(&(x + arg)) { arg } else { -arg };
+            x += arg;
+            format!("addx {arg}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,141 @@
+//! Monkey troops: `size` monkeys, each testing a small prime and throwing
+//! to two other monkeys. Part 1 keeps exact worry levels, so operations
+//! are toned down until its 20 rounds fit in 64 bits.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::Params;
+
+const PRIMES: &[u64] = &[2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+enum Operation {
+    Square,
+    Mul(u64),
+    Add(u64),
+}
+
+struct Monkey {
+    items: Vec<u64>,
+    operation: Operation,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
+}
+
+fn other_monkey(rng: &mut impl Rng, monkeys: usize, except: &[usize]) -> usize {
+    loop {
+        let monkey = rng.gen_range(0..monkeys);
+        if !except.contains(&monkey) {
+            return monkey;
+        }
+    }
+}
+
+/// Part 1 played out with exact worry levels, or `None` once one of them
+/// no longer fits in 64 bits.
+fn part1(troop: &[Monkey]) -> Option<u64> {
+    let mut items: Vec<Vec<u64>> = troop.iter().map(|monkey| monkey.items.clone()).collect();
+    let mut inspected = vec![0; troop.len()];
+    for _round in 0..20 {
+        for (id, monkey) in troop.iter().enumerate() {
+            for old in std::mem::take(&mut items[id]) {
+                inspected[id] += 1;
+                let new = match monkey.operation {
+                    Operation::Square => old.checked_mul(old)?,
+                    Operation::Mul(by) => old.checked_mul(by)?,
+                    Operation::Add(by) => old.checked_add(by)?,
+                } / 3;
+                let to = if new.is_multiple_of(monkey.divisor) { monkey.if_true } else { monkey.if_false };
+                items[to].push(new);
+            }
+        }
+    }
+    inspected.sort_unstable();
+    Some(inspected.iter().rev().take(2).product())
+}
+
+/// Swaps the first squaring monkey for a multiplying one, or failing that
+/// the first multiplying monkey for an adding one. Adding alone never
+/// overflows, so repeating this ends with a troop that fits.
+fn tone_down(rng: &mut impl Rng, troop: &mut [Monkey]) {
+    let operations = || troop.iter().map(|monkey| &monkey.operation);
+    let (at, operation) = match operations().position(|op| matches!(op, Operation::Square)) {
+        Some(at) => (at, Operation::Mul(rng.gen_range(2..20))),
+        None => {
+            let at = operations().position(|op| matches!(op, Operation::Mul(_))).unwrap();
+            (at, Operation::Add(rng.gen_range(1..10)))
+        }
+    };
+    troop[at].operation = operation;
+}
+
+fn troop(rng: &mut impl Rng, params: &Params) -> Vec<Monkey> {
+    let monkeys = params.size.max(3);
+    let mut troop: Vec<Monkey> = (0..monkeys)
+        .map(|id| {
+            let items = (0..rng.gen_range(1..=6)).map(|_| rng.gen_range(50..100)).collect();
+            let operation = match rng.gen_range(0..3) {
+                0 => Operation::Mul(rng.gen_range(2..20)),
+                1 => Operation::Add(rng.gen_range(1..10)),
+                _ => Operation::Square,
+            };
+            let divisor = *PRIMES.choose(rng).unwrap();
+            let if_true = other_monkey(rng, monkeys, &[id]);
+            let if_false = other_monkey(rng, monkeys, &[id, if_true]);
+            Monkey { items, operation, divisor, if_true, if_false }
+        })
+        .collect();
+    while part1(&troop).is_none() {
+        tone_down(rng, &mut troop);
+    }
+    troop
+}
+
+fn format(troop: &[Monkey]) -> String {
+    troop
+        .iter()
+        .enumerate()
+        .map(|(id, monkey)| {
+            let items = monkey.items.iter().map(u64::to_string).collect::<Vec<_>>().join(", ");
+            let operation = match monkey.operation {
+                Operation::Square => "old * old".to_string(),
+                Operation::Mul(by) => format!("old * {by}"),
+                Operation::Add(by) => format!("old + {by}"),
+            };
+            let Monkey { divisor, if_true, if_false, .. } = monkey;
+            format!(
+                "Monkey {id}:
+  Starting items: {items}
+  Operation: new = {operation}
+  Test: divisible by {divisor}
+    If true: throw to monkey {if_true}
+    If false: throw to monkey {if_false}"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    format(&troop(rng, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn part1_matches_an_exact_simulation() {
+        let day = aoc::DAYS.iter().find(|day| day.day == 11).unwrap();
+        let mut squaring = 0;
+        for seed in 0..20 {
+            let troop = troop(&mut StdRng::seed_from_u64(seed), &Params { size: 8, depth: 0 });
+            squaring += troop.iter().filter(|monkey| matches!(monkey.operation, Operation::Square)).count();
+            let report = (day.solve)(&format(&troop), &[1]).unwrap();
+            assert_eq!(report.parts[0].answer, part1(&troop).unwrap().to_string(), "seed {seed}");
+        }
+        // Toning down must leave some troops that square.
+        assert!(squaring > 0);
+    }
+}
@@ -0,0 +1,46 @@
+//! Heightmaps: `size` columns (at least 52) by `size / 2` rows. Heights
+//! rise towards `E` by at most one per step, so `E` is reachable from every
+//! square, and `S` starts far enough away to sit among the lowest ones.
+
+use rand::Rng;
+
+use crate::Params;
+
+fn distance(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let width = params.size.max(52);
+    let height = width / 2;
+    let mut square = || (rng.gen_range(0..width), rng.gen_range(0..height));
+    let goal = square();
+    let start = loop {
+        let start = square();
+        if distance(start, goal) >= 25 {
+            break start;
+        }
+    };
+
+    // Every `step` squares away from the goal the height drops by one;
+    // adding less than a step of jitter keeps neighbours within one height
+    // of each other, and the squares around `S` at most at `b`.
+    let step = (distance(start, goal) - 1) / 24;
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    if (x, y) == start {
+                        return 'S';
+                    }
+                    if (x, y) == goal {
+                        return 'E';
+                    }
+                    let drop = (distance((x, y), goal) + rng.gen_range(0..step)) / step;
+                    (b'z' - drop.min(25) as u8) as char
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,61 @@
+//! Distress signals: `size` pairs of packets, each nested exactly `depth`
+//! lists deep along one path, at most [`MAX_DEPTH`] as that is where the
+//! parser draws the line.
+
+use day13::MAX_DEPTH;
+use rand::Rng;
+
+use crate::Params;
+
+/// A list up to `depth` lists deep, or exactly that deep along one item
+/// when `spine` is set.
+fn packet(rng: &mut impl Rng, depth: usize, spine: bool) -> String {
+    let len = rng.gen_range(usize::from(spine)..=4);
+    let deepest = rng.gen_range(0..len.max(1));
+    let items = (0..len)
+        .map(|i| {
+            let spine = spine && i == deepest;
+            if depth > 1 && (spine || rng.gen_bool(0.4)) {
+                packet(rng, depth - 1, spine)
+            } else {
+                rng.gen_range(0..=10).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("[{items}]")
+}
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let depth = params.depth.clamp(1, MAX_DEPTH);
+    (0..params.size.max(1))
+        .map(|_| format!("{}\n{}", packet(rng, depth, true), packet(rng, depth, true)))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day13::Packet;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// How many lists deep `packet` nests.
+    fn depth(packet: &Packet) -> usize {
+        match packet {
+            Packet::Integer(_) => 0,
+            Packet::List(items) => 1 + items.iter().map(depth).max().unwrap_or(0),
+        }
+    }
+
+    #[test]
+    fn the_deepest_allowed_packets_parse() {
+        let rng = &mut StdRng::seed_from_u64(0);
+        let input = generate(rng, &Params { size: 2, depth: MAX_DEPTH + 1 });
+        for packet in input.lines().filter(|line| !line.is_empty()) {
+            let packet: Packet = packet.parse().unwrap();
+            assert_eq!(depth(&packet), MAX_DEPTH);
+        }
+        assert!(day13::part1(&input).is_ok());
+    }
+}
@@ -0,0 +1,16 @@
+//! Strategy guides: `size` rounds of rock, paper, scissors.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    (0..params.size.max(1))
+        .map(|_| {
+            let opponent = ["A", "B", "C"].choose(rng).unwrap();
+            let response = ["X", "Y", "Z"].choose(rng).unwrap();
+            format!("{opponent} {response}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,41 @@
+//! Rucksacks: `size` groups of three elves. Each rucksack's compartments
+//! share exactly one item and each group shares exactly one badge.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::Params;
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn rucksack(rng: &mut impl Rng, pool: &[u8], badge: u8) -> String {
+    // One item goes in both compartments, the rest of the pool is split
+    // between them so nothing else is shared.
+    let (shared, rest) = pool.split_first().unwrap();
+    let (left, right) = rest.split_at(rest.len() / 2);
+    let half = rng.gen_range(2..=16);
+
+    let mut first = vec![*shared, badge];
+    first.extend((2..half).map(|_| *left.choose(rng).unwrap()));
+    first.shuffle(rng);
+    let mut second = vec![*shared];
+    second.extend((1..half).map(|_| *right.choose(rng).unwrap()));
+    second.shuffle(rng);
+
+    first.extend(second);
+    String::from_utf8(first).unwrap()
+}
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    (0..params.size.max(1))
+        .flat_map(|_| {
+            let mut items = ITEMS.to_vec();
+            items.shuffle(rng);
+            let (badge, rest) = items.split_first().unwrap();
+            // 51 items left: 17 private ones for each elf.
+            rest.chunks(17)
+                .map(|pool| rucksack(rng, pool, *badge))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
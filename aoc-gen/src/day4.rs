@@ -0,0 +1,18 @@
+//! Section assignments: `size` pairs of ranges within sections 1 to 99.
+
+use rand::Rng;
+
+use crate::Params;
+
+fn range(rng: &mut impl Rng) -> String {
+    let start = rng.gen_range(1..=99);
+    let end = rng.gen_range(start..=99);
+    format!("{start}-{end}")
+}
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    (0..params.size.max(1))
+        .map(|_| format!("{},{}", range(rng), range(rng)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,56 @@
+//! Crate diagrams: `size` stacks up to `depth` crates high, followed by
+//! ten moves per stack that are all legal for both cranes.
+
+use rand::Rng;
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let stacks = params.size.max(2);
+    let mut heights: Vec<usize> = (0..stacks)
+        .map(|_| rng.gen_range(0..=params.depth.max(1)))
+        .collect();
+    heights[0] = heights[0].max(1);
+    let tallest = *heights.iter().max().unwrap();
+
+    let mut lines: Vec<String> = (0..tallest)
+        .rev()
+        .map(|level| {
+            heights
+                .iter()
+                .map(|&height| {
+                    if height > level {
+                        format!("[{}]", rng.gen_range('A'..='Z'))
+                    } else {
+                        "   ".to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    lines.push(
+        (1..=stacks)
+            .map(|label| format!(" {label:<2}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    lines.push(String::new());
+
+    // Track only the heights: a move is legal as long as `from` holds at
+    // least as many crates as are moved.
+    for _ in 0..stacks * 10 {
+        let from = loop {
+            let from = rng.gen_range(0..stacks);
+            if heights[from] > 0 {
+                break from;
+            }
+        };
+        let to = (from + rng.gen_range(1..stacks)) % stacks;
+        let number = rng.gen_range(1..=heights[from].min(10));
+        heights[from] -= number;
+        heights[to] += number;
+        lines.push(format!("move {number} from {} to {}", from + 1, to + 1));
+    }
+    lines.join("\n")
+}
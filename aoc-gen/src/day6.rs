@@ -0,0 +1,41 @@
+//! Datastreams: `size` characters drawn from only three letters, then
+//! fourteen distinct letters starting with the last of those. Every window
+//! across the seam holds that letter twice, so neither marker shows up
+//! before the planted letters and the start-of-message marker ends the
+//! stream.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let mut signal: Vec<u8> = (0..params.size)
+        .map(|_| *b"abc".choose(rng).unwrap())
+        .collect();
+    let mut letters = b"abcdefghijklmnopqrstuvwxyz".to_vec();
+    letters.shuffle(rng);
+    // The planted letters repeat the last filler one first.
+    if let Some(&last) = signal.last() {
+        letters.retain(|&letter| letter != last);
+        letters.insert(0, last);
+    }
+    signal.extend(&letters[..14]);
+    String::from_utf8(signal).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn markers_only_show_up_in_the_planted_letters() {
+        let day = aoc::DAYS.iter().find(|day| day.day == 6).unwrap();
+        for (seed, size) in (0..20).zip([0, 1, 2, 3, 50].into_iter().cycle()) {
+            let input = generate(&mut StdRng::seed_from_u64(seed), &Params { size, depth: 0 });
+            let report = (day.solve)(&input, &[1, 2]).unwrap();
+            let answers: Vec<&str> = report.parts.iter().map(|part| part.answer.as_str()).collect();
+            assert_eq!(answers, [(size + 4).to_string(), (size + 14).to_string()], "seed {seed}");
+        }
+    }
+}
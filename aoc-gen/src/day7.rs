@@ -0,0 +1,73 @@
+//! Terminal transcripts: a tree of `size` directories nested at most
+//! `depth` deep, explored depth first, using under 70 000 000 bytes.
+
+use rand::Rng;
+
+use crate::Params;
+
+/// Spells `n` in lowercase letters: a, b, ..., z, ba, bb, ...
+fn name(mut n: usize) -> String {
+    let mut letters = vec![];
+    loop {
+        letters.push(b'a' + (n % 26) as u8);
+        n /= 26;
+        if n == 0 {
+            break;
+        }
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let size = params.size.max(1);
+    let max_depth = params.depth.max(1);
+
+    // Directory 0 is the root; every other one picks an earlier parent,
+    // often the directory right before it so the tree also grows deep.
+    let mut depth = vec![0];
+    let mut children: Vec<Vec<usize>> = vec![vec![]];
+    for dir in 1..size {
+        let parent = if rng.gen_bool(0.5) && depth[dir - 1] < max_depth {
+            dir - 1
+        } else {
+            loop {
+                let parent = rng.gen_range(0..dir);
+                if depth[parent] < max_depth {
+                    break parent;
+                }
+            }
+        };
+        depth.push(depth[parent] + 1);
+        children.push(vec![]);
+        children[parent].push(dir);
+    }
+
+    let files = size * 5;
+    let largest = (70_000_000 / files).clamp(1, 300_000);
+    let mut lines = vec!["$ cd /".to_string()];
+
+    // Each entry is a directory to enter, or `None` to climb back out.
+    let mut stack = vec![Some(0)];
+    while let Some(entry) = stack.pop() {
+        let Some(dir) = entry else {
+            lines.push("$ cd ..".to_string());
+            continue;
+        };
+        if dir != 0 {
+            lines.push(format!("$ cd {}", name(dir)));
+        }
+        lines.push("$ ls".to_string());
+        for &child in &children[dir] {
+            lines.push(format!("dir {}", name(child)));
+        }
+        for file in 0..rng.gen_range(1..=5) {
+            lines.push(format!("{} {}.txt", rng.gen_range(1..=largest), name(file)));
+        }
+        for &child in children[dir].iter().rev() {
+            stack.push(None);
+            stack.push(Some(child));
+        }
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,13 @@
+//! Tree maps: a `size` by `size` grid of heights.
+
+use rand::Rng;
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    let side = params.size.max(1);
+    (0..side)
+        .map(|_| (0..side).map(|_| rng.gen_range('0'..='9')).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,15 @@
+//! Rope motions: `size` moves of 1 to 20 steps each.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::Params;
+
+pub fn generate(rng: &mut impl Rng, params: &Params) -> String {
+    (0..params.size.max(1))
+        .map(|_| {
+            let direction = ["R", "U", "L", "D"].choose(rng).unwrap();
+            format!("{direction} {}", rng.gen_range(1..=20))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,74 @@
+//! Random, arbitrarily large puzzle inputs in exactly the formats the
+//! day parsers accept, for stress testing and benchmarking.
+
+use rand::{rngs::StdRng, SeedableRng};
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+/// How large an input to generate. Each day's module documents what
+/// `size` counts; `depth` only matters to the nested formats.
+#[derive(Debug, Clone, Copy)]
+pub struct Params {
+    pub size: usize,
+    pub depth: usize,
+}
+
+/// Generates an input for `day`; the same seed and parameters always give
+/// the same input. Returns `None` for a day with no generator.
+pub fn generate(day: u8, seed: u64, params: &Params) -> Option<String> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let input = match day {
+        1 => day1::generate(rng, params),
+        2 => day2::generate(rng, params),
+        3 => day3::generate(rng, params),
+        4 => day4::generate(rng, params),
+        5 => day5::generate(rng, params),
+        6 => day6::generate(rng, params),
+        7 => day7::generate(rng, params),
+        8 => day8::generate(rng, params),
+        9 => day9::generate(rng, params),
+        10 => day10::generate(rng, params),
+        11 => day11::generate(rng, params),
+        12 => day12::generate(rng, params),
+        13 => day13::generate(rng, params),
+        _ => return None,
+    };
+    Some(input + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_inputs_solve() {
+        for day in aoc::DAYS {
+            for seed in 0..3 {
+                let params = Params { size: 60, depth: 8 };
+                let input = generate(day.day, seed, &params).unwrap();
+                if let Err(err) = (day.solve)(&input, &[1, 2]) {
+                    panic!("seed {seed}: {err}\n{input}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seeds_are_reproducible() {
+        let params = Params { size: 20, depth: 5 };
+        assert_eq!(generate(7, 42, &params), generate(7, 42, &params));
+        assert_ne!(generate(7, 42, &params), generate(7, 43, &params));
+    }
+}
@@ -0,0 +1,46 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use aoc_gen::{generate, Params};
+use clap::Parser;
+
+/// Generate a large, valid puzzle input for one day
+#[derive(Parser)]
+#[command(name = "aoc-gen")]
+struct Cli {
+    /// Day to generate an input for
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=13))]
+    day: u8,
+    /// Seed for the random generator
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Elves, rounds, stacks, directories, grid side, moves, instructions,
+    /// monkeys, map width or packet pairs, depending on the day
+    #[arg(long, default_value_t = 1000)]
+    size: usize,
+    /// Maximum stack height (day 5) or nesting depth (days 7 and 13)
+    #[arg(long, default_value_t = 10)]
+    depth: usize,
+    /// File to write, stdout when omitted
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let params = Params { size: cli.size, depth: cli.depth };
+    let Some(input) = generate(cli.day, cli.seed, &params) else {
+        eprintln!("day {}: no generator", cli.day);
+        return ExitCode::FAILURE;
+    };
+
+    match cli.output {
+        Some(path) => {
+            if let Err(err) = fs::write(&path, input) {
+                eprintln!("cannot write {}: {err}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{input}"),
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,320 @@
+//! Python bindings for the solvers, as the `aoc2022` extension module.
+//!
+//! Every day is a submodule with `part1(input)` and `part2(input)`, which
+//! return the answer as a string like the runner prints it, and
+//! `parse(input)`, which returns the parsed input as native Python values.
+//! Some days also expose intermediate results, such as
+//! `aoc2022.day7.directory_sizes(input)` or `aoc2022.day13.compare(a, b)`.
+//! Inputs the solvers reject raise `ValueError` with the error's location.
+//!
+//! Build and test with `maturin develop && pytest` from this directory.
+
+// `#[pyfunction]` expands to an `Into<PyErr>` conversion clippy flags.
+#![allow(clippy::useless_conversion)]
+
+use aoc_common::{normalize, Direction, Error, Point, Solution};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+fn value_error(err: Error) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn solve<S: Solution>(input: &str, part: u8) -> PyResult<String> {
+    let input = normalize(input);
+    let parsed = S::parse(&input).map_err(value_error)?;
+    match part {
+        1 => S::part1(&parsed),
+        _ => S::part2(&parsed),
+    }
+    .map_err(value_error)
+}
+
+/// A `dayN` submodule with `part1` and `part2` for `$solution`, followed
+/// by the day's `$extra` functions, defined among `$item`s.
+macro_rules! day {
+    ($name:ident, $solution:ty, [$($extra:ident),*] $($item:item)*) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// The answer to part 1 for the puzzle input.
+            #[pyfunction]
+            fn part1(input: &str) -> PyResult<String> {
+                solve::<$solution>(input, 1)
+            }
+
+            /// The answer to part 2 for the puzzle input.
+            #[pyfunction]
+            fn part2(input: &str) -> PyResult<String> {
+                solve::<$solution>(input, 2)
+            }
+
+            $($item)*
+
+            /// Adds the submodule to `parent`, importable as `aoc2022.dayN`.
+            pub fn register(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+                let py = parent.py();
+                let module = PyModule::new_bound(py, stringify!($name))?;
+                module.add_function(wrap_pyfunction!(part1, &module)?)?;
+                module.add_function(wrap_pyfunction!(part2, &module)?)?;
+                $(module.add_function(wrap_pyfunction!($extra, &module)?)?;)*
+                parent.add_submodule(&module)?;
+                py.import_bound("sys")?
+                    .getattr("modules")?
+                    .set_item(concat!("aoc2022.", stringify!($name)), &module)
+            }
+        }
+    };
+}
+
+day!(day1, ::day1::Day1, [parse]
+    /// The calories of every item, one list per elf.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<Vec<u64>>> {
+        ::day1::Day1::parse(&normalize(input)).map_err(value_error)
+    }
+);
+day!(day2, ::day2::Day2, [parse]
+    /// Every round as the opponent's move and the response symbol, such as
+    /// `("Rock", "Y")`.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<(String, String)>> {
+        let game = ::day2::Game::rock_paper_scissors();
+        let guide = ::day2::Day2::parse(&normalize(input)).map_err(value_error)?;
+        Ok(guide
+            .into_iter()
+            .map(|(theirs, response)| {
+                (game.name(theirs).to_string(), game.symbol(response).to_string())
+            })
+            .collect())
+    }
+);
+day!(day3, ::day3::Day3, [parse]
+    /// The contents of every rucksack.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<String>> {
+        let input = normalize(input);
+        let rucksacks = ::day3::Day3::parse(&input).map_err(value_error)?;
+        Ok(rucksacks.into_iter().map(str::to_string).collect())
+    }
+);
+day!(day4, ::day4::Day4, [parse]
+    /// The first and last section of a range.
+    type Sections = (u32, u32);
+
+    /// Every pair's section ranges, as `((2, 4), (6, 8))`.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<(Sections, Sections)>> {
+        let pairs = ::day4::Day4::parse(&normalize(input)).map_err(value_error)?;
+        Ok(pairs
+            .into_iter()
+            .map(|(a, b)| ((*a.start(), *a.end()), (*b.start(), *b.end())))
+            .collect())
+    }
+);
+day!(day5, ::day5::Day5, [parse]
+    /// A move as `(number, from, to)`.
+    type Move = (u32, u32, u32);
+
+    /// The crates on each stack, bottom first, and every move with the
+    /// stacks numbered from 1.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<(Vec<Vec<String>>, Vec<Move>)> {
+        let input = normalize(input);
+        let procedure = ::day5::Day5::parse(&input).map_err(value_error)?;
+        let stacks = procedure
+            .stacks()
+            .iter()
+            .map(|stack| stack.iter().map(|c| c.to_string()).collect())
+            .collect();
+        Ok((stacks, procedure.moves().collect()))
+    }
+);
+day!(day6, ::day6::Day6, [parse]
+    /// The datastream, one character per element.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<char>> {
+        ::day6::Day6::parse(&normalize(input)).map_err(value_error)
+    }
+);
+day!(day7, ::day7::Day7, [parse, directory_sizes]
+    /// Every command, as `("cd", "a")` or as `("ls", listing)` with each
+    /// listed name and its size, `None` for a directory.
+    #[pyfunction]
+    fn parse(py: Python<'_>, input: &str) -> PyResult<Vec<(&'static str, PyObject)>> {
+        use ::day7::{Cd, Files, Operation};
+
+        let input = normalize(input);
+        let commands = ::day7::Day7::parse(&input).map_err(value_error)?;
+        Ok(commands
+            .iter()
+            .map(|command| match command {
+                Operation::Cd(Cd::Root) => ("cd", "/".into_py(py)),
+                Operation::Cd(Cd::Up) => ("cd", "..".into_py(py)),
+                Operation::Cd(Cd::Down(name)) => ("cd", name.into_py(py)),
+                Operation::Ls(files) => {
+                    let listing: Vec<(&str, Option<u64>)> = files
+                        .iter()
+                        .map(|file| match file {
+                            Files::File { size, name } => (*name, Some(*size)),
+                            Files::Dir(name) => (*name, None),
+                        })
+                        .collect();
+                    ("ls", listing.into_py(py))
+                }
+            })
+            .collect())
+    }
+
+    /// The total size of every directory, keyed by its path; the root
+    /// directory is `""` and the others look like `"/a/e"`.
+    #[pyfunction]
+    fn directory_sizes(input: &str) -> PyResult<::day7::DirectorySizes> {
+        let input = normalize(input);
+        let commands = ::day7::Day7::parse(&input).map_err(value_error)?;
+        ::day7::directory_sizes(&commands).map_err(value_error)
+    }
+);
+day!(day8, ::day8::Day8, [parse]
+    /// The height of every tree, one list per row.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<Vec<u32>>> {
+        let grid = ::day8::Day8::parse(&normalize(input)).map_err(value_error)?;
+        Ok(grid.rows().map(<[u32]>::to_vec).collect())
+    }
+);
+day!(day9, ::day9::Day9, [parse]
+    /// Every motion of the head as its direction and step count, such as
+    /// `("R", 4)`.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<(&'static str, u32)>> {
+        let motions = ::day9::Day9::parse(&normalize(input)).map_err(value_error)?;
+        Ok(motions
+            .into_iter()
+            .map(|motion| {
+                let direction = match motion.direction() {
+                    Direction::Up => "U",
+                    Direction::Right => "R",
+                    Direction::Down => "D",
+                    Direction::Left => "L",
+                };
+                (direction, motion.count())
+            })
+            .collect())
+    }
+);
+day!(day10, ::day10::Day10, [parse]
+    /// Every instruction with its argument, such as `("addx", 3)` or
+    /// `("noop", None)`.
+    #[pyfunction]
+    fn parse(input: &str) -> PyResult<Vec<(&'static str, Option<i32>)>> {
+        let program = ::day10::Day10::parse(&normalize(input)).map_err(value_error)?;
+        Ok(program
+            .into_iter()
+            .map(|command| (command.instruction(), command.argument()))
+            .collect())
+    }
+);
+day!(day11, ::day11::Day11, [parse, inspection_counts]
+    /// Every monkey as a dict of the worry levels of its `items`, its
+    /// `operation` such as `"old * 19"`, the `divisor` it tests for and the
+    /// monkeys it throws to `if_true` and `if_false`.
+    #[pyfunction]
+    fn parse<'py>(py: Python<'py>, input: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let monkeys = ::day11::Day11::parse(&normalize(input)).map_err(value_error)?;
+        monkeys
+            .iter()
+            .map(|monkey| {
+                let (if_true, if_false) = monkey.recipients();
+                let dict = PyDict::new_bound(py);
+                dict.set_item("items", monkey.items().collect::<Vec<_>>())?;
+                dict.set_item("operation", monkey.operation())?;
+                dict.set_item("divisor", monkey.divisor())?;
+                dict.set_item("if_true", if_true)?;
+                dict.set_item("if_false", if_false)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// How many items each monkey inspects over `rounds` rounds, with the
+    /// worry level divided by three after each inspection when `relief`.
+    #[pyfunction]
+    #[pyo3(signature = (input, rounds = 20, relief = true))]
+    fn inspection_counts(input: &str, rounds: usize, relief: bool) -> PyResult<Vec<u64>> {
+        let monkeys = ::day11::Day11::parse(&normalize(input)).map_err(value_error)?;
+        ::day11::inspection_counts(&monkeys, rounds, relief).map_err(value_error)
+    }
+);
+day!(day12, ::day12::Day12, [parse, shortest_path]
+    /// The heightmap as a dict of its `grid` rows, with `S` and `E` in
+    /// place, and the `start` and `goal` as `(x, y)`.
+    #[pyfunction]
+    fn parse<'py>(py: Python<'py>, grid: &str) -> PyResult<Bound<'py, PyDict>> {
+        let heightmap = ::day12::Day12::parse(&normalize(grid)).map_err(value_error)?;
+        let rows: Vec<String> = heightmap.map().rows().map(|row| row.iter().collect()).collect();
+        let point = |point: Point| (point.x, point.y);
+        let dict = PyDict::new_bound(py);
+        dict.set_item("grid", rows)?;
+        dict.set_item("start", point(heightmap.start()))?;
+        dict.set_item("goal", point(heightmap.goal()))?;
+        Ok(dict)
+    }
+
+    /// The fewest steps from `S` to `E` on the heightmap `grid`, or `None`
+    /// when `E` cannot be reached.
+    #[pyfunction]
+    fn shortest_path(grid: &str) -> PyResult<Option<i32>> {
+        let heightmap = ::day12::Day12::parse(&normalize(grid)).map_err(value_error)?;
+        Ok(heightmap.steps())
+    }
+);
+day!(day13, ::day13::Day13, [parse, compare]
+    /// `packet` as nested lists of ints.
+    fn packet(py: Python<'_>, packet: &::day13::Packet) -> PyObject {
+        match packet {
+            ::day13::Packet::Integer(n) => n.into_py(py),
+            ::day13::Packet::List(items) => {
+                let items: Vec<PyObject> = items.iter().map(|item| self::packet(py, item)).collect();
+                items.into_py(py)
+            }
+        }
+    }
+
+    /// Every pair of packets, each as nested lists of ints.
+    #[pyfunction]
+    fn parse(py: Python<'_>, input: &str) -> PyResult<Vec<(PyObject, PyObject)>> {
+        let pairs = ::day13::Day13::parse(&normalize(input)).map_err(value_error)?;
+        Ok(pairs
+            .iter()
+            .map(|pair| (packet(py, &pair.left), packet(py, &pair.right)))
+            .collect())
+    }
+
+    /// `-1`, `0` or `1` as the packet `left` sorts before, with or after
+    /// `right`, so it works with `functools.cmp_to_key`.
+    #[pyfunction]
+    fn compare(left: &str, right: &str) -> PyResult<i8> {
+        let left: ::day13::Packet = left.parse().map_err(value_error)?;
+        let right: ::day13::Packet = right.parse().map_err(value_error)?;
+        Ok(left.cmp(&right) as i8)
+    }
+);
+
+#[pymodule]
+fn aoc2022(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    day1::register(module)?;
+    day2::register(module)?;
+    day3::register(module)?;
+    day4::register(module)?;
+    day5::register(module)?;
+    day6::register(module)?;
+    day7::register(module)?;
+    day8::register(module)?;
+    day9::register(module)?;
+    day10::register(module)?;
+    day11::register(module)?;
+    day12::register(module)?;
+    day13::register(module)?;
+    Ok(())
+}
@@ -0,0 +1,168 @@
+from functools import cmp_to_key
+from pathlib import Path
+
+import pytest
+
+import aoc2022
+
+EXAMPLES = Path(__file__).parent / "examples"
+
+SCREEN = """\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"""
+
+ANSWERS = {
+    1: ("24000", "45000"),
+    2: ("15", "12"),
+    3: ("157", "70"),
+    4: ("2", "4"),
+    5: ("CMZ", "MCD"),
+    6: ("7", "19"),
+    7: ("95437", "24933642"),
+    8: ("21", "8"),
+    9: ("13", "1"),
+    10: ("13140", SCREEN),
+    11: ("10605", "2713310158"),
+    12: ("31", "29"),
+    13: ("13", "140"),
+}
+
+
+def example(day):
+    return (EXAMPLES / f"day{day}.txt").read_text()
+
+
+@pytest.mark.parametrize("day", sorted(ANSWERS))
+def test_examples(day):
+    module = getattr(aoc2022, f"day{day}")
+    assert (module.part1(example(day)), module.part2(example(day))) == ANSWERS[day]
+
+
+def test_submodules_import():
+    from aoc2022 import day13
+    import aoc2022.day7
+
+    assert day13 is aoc2022.day13
+    assert aoc2022.day7.part1(example(7)) == "95437"
+
+
+def test_errors_carry_their_location():
+    with pytest.raises(ValueError, match=r"^day 1, line 3, column 1: invalid calories"):
+        aoc2022.day1.part1("1000\n\nabc\n")
+
+
+def test_day1_parse():
+    assert aoc2022.day1.parse(example(1))[:2] == [[1000, 2000, 3000], [4000]]
+
+
+def test_day2_parse():
+    assert aoc2022.day2.parse(example(2)) == [("Rock", "Y"), ("Paper", "X"), ("Scissors", "Z")]
+
+
+def test_day3_parse():
+    rucksacks = aoc2022.day3.parse(example(3))
+    assert len(rucksacks) == 6
+    assert rucksacks[0] == "vJrwpWtwJgWrhcsFMMfFFhFp"
+
+
+def test_day4_parse():
+    assert aoc2022.day4.parse(example(4))[:2] == [((2, 4), (6, 8)), ((2, 3), (4, 5))]
+
+
+def test_day5_parse():
+    stacks, moves = aoc2022.day5.parse(example(5))
+    assert stacks == [["Z", "N"], ["M", "C", "D"], ["P"]]
+    assert moves == [(1, 2, 1), (3, 1, 3), (2, 2, 1), (1, 1, 2)]
+
+
+def test_day6_parse():
+    assert "".join(aoc2022.day6.parse(example(6))) == "mjqjpqmgbljsphdztnvjfqwrcgsmlb"
+
+
+def test_day7_parse():
+    commands = aoc2022.day7.parse(example(7))
+    assert commands[:2] == [
+        ("cd", "/"),
+        ("ls", [("a", None), ("b.txt", 14848514), ("c.dat", 8504156), ("d", None)]),
+    ]
+    assert ("cd", "..") in commands
+
+
+def test_day7_directory_sizes():
+    assert aoc2022.day7.directory_sizes(example(7)) == {
+        "": 48381165,
+        "/a": 94853,
+        "/a/e": 584,
+        "/d": 24933642,
+    }
+
+
+def test_day8_parse():
+    grid = aoc2022.day8.parse(example(8))
+    assert grid[0] == [3, 0, 3, 7, 3]
+    assert len(grid) == 5
+
+
+def test_day9_parse():
+    assert aoc2022.day9.parse(example(9))[:4] == [("R", 4), ("U", 4), ("L", 3), ("D", 1)]
+
+
+def test_day10_parse():
+    program = aoc2022.day10.parse(example(10))
+    assert program[:2] == [("addx", 15), ("addx", -11)]
+    assert program[9] == ("noop", None)
+
+
+def test_day11_parse():
+    monkeys = aoc2022.day11.parse(example(11))
+    assert len(monkeys) == 4
+    assert monkeys[0] == {
+        "items": [79, 98],
+        "operation": "old * 19",
+        "divisor": 23,
+        "if_true": 2,
+        "if_false": 3,
+    }
+    assert monkeys[2]["operation"] == "old * old"
+
+
+def test_day11_inspection_counts():
+    assert aoc2022.day11.inspection_counts(example(11)) == [101, 95, 7, 105]
+    counts = aoc2022.day11.inspection_counts(example(11), rounds=10_000, relief=False)
+    assert counts == [52166, 47830, 1938, 52013]
+
+
+def test_day12_parse():
+    heightmap = aoc2022.day12.parse(example(12))
+    assert heightmap["grid"][0] == "Sabqponm"
+    assert len(heightmap["grid"]) == 5
+    assert (heightmap["start"], heightmap["goal"]) == ((0, 0), (5, 2))
+
+
+def test_day12_shortest_path():
+    assert aoc2022.day12.shortest_path(example(12)) == 31
+    assert aoc2022.day12.shortest_path("Sz\nzE") is None
+
+
+def test_day13_parse():
+    pairs = aoc2022.day13.parse(example(13))
+    assert len(pairs) == 8
+    assert pairs[0] == ([1, 1, 3, 1, 1], [1, 1, 5, 1, 1])
+    assert pairs[1] == ([[1], [2, 3, 4]], [[1], 4])
+    assert pairs[6] == ([[[]]], [[]])
+
+
+def test_day13_compare():
+    assert aoc2022.day13.compare("[1,1,3,1,1]", "[1,1,5,1,1]") == -1
+    assert aoc2022.day13.compare("[[1],4]", "[[1],[2,3,4]]") == 1
+    assert aoc2022.day13.compare("[[2]]", "[2]") == 0
+    packets = ["[[6]]", "[]", "[[2]]", "[1,1,3,1,1]"]
+    ordered = sorted(packets, key=cmp_to_key(aoc2022.day13.compare))
+    assert ordered == ["[]", "[1,1,3,1,1]", "[[2]]", "[[6]]"]
+    with pytest.raises(ValueError):
+        aoc2022.day13.compare("[1,", "[]")
@@ -0,0 +1,181 @@
+//! A small HTTP front end to the solvers, for callers that cannot link Rust.
+//!
+//! `POST /day/{n}/part/{p}` takes the raw puzzle input as its body and
+//! answers with the same JSON object `aoc run --format json` prints:
+//!
+//! ```text
+//! {"day":1,"part":1,"answer":"24000","parse_ms":0.01,"solve_ms":0.002}
+//! ```
+//!
+//! An input the solver rejects gets a 400 whose body carries the error and,
+//! when the solver knows it, where in the input it was found:
+//!
+//! ```text
+//! {"day":1,"error":"invalid calories \"abc\": invalid digit found in string","line":3,"column":1}
+//! ```
+//!
+//! A solver that panics gets a 500, and the server goes on serving.
+
+use std::{
+    io::Read,
+    panic::{self, AssertUnwindSafe},
+};
+
+use aoc::days::{self, Report};
+use aoc_common::Error;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Server};
+
+/// Inputs are a few kilobytes; refuse anything absurdly larger.
+pub const MAX_INPUT: u64 = 16 << 20;
+
+/// A response before it is written out: a status code and a JSON body.
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// Splits `/day/{n}/part/{p}` into its day and part.
+fn parse_path(url: &str) -> Option<(u8, u8)> {
+    let path = url.split('?').next().unwrap_or_default();
+    match path.split('/').collect::<Vec<_>>()[..] {
+        ["", "day", day, "part", part] => Some((day.parse().ok()?, part.parse().ok()?)),
+        _ => None,
+    }
+}
+
+/// Answers one request. `body` is `None` when it was larger than
+/// [`MAX_INPUT`].
+pub fn route(method: &Method, url: &str, body: Option<&[u8]>) -> Response {
+    let Some((day, part)) = parse_path(url) else {
+        return Response::error(404, "expected /day/{n}/part/{p}");
+    };
+    let Some(solution) = days::get(day) else {
+        return Response::error(404, format!("there is no solver for day {day}"));
+    };
+    if !matches!(part, 1 | 2) {
+        return Response::error(404, "there are only parts 1 and 2");
+    }
+    if *method != Method::Post {
+        return Response::error(405, "send the puzzle input with POST");
+    }
+    let Some(body) = body else {
+        return Response::error(413, format!("the input is larger than {MAX_INPUT} bytes"));
+    };
+    let Ok(input) = std::str::from_utf8(body) else {
+        return Response::error(400, "the input is not UTF-8");
+    };
+
+    answer(day, part, || (solution.solve)(input, &[part]))
+}
+
+/// Runs `solve` and answers with its report or error. A solver that panics
+/// gets a 500 rather than taking the whole server down with it.
+fn answer(day: u8, part: u8, solve: impl FnOnce() -> Result<Report, Error>) -> Response {
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(Ok(report)) => {
+            let solved = &report.parts[0];
+            Response {
+                status: 200,
+                body: json!({
+                    "day": day,
+                    "part": part,
+                    "answer": solved.answer,
+                    "parse_ms": report.parse.as_secs_f64() * 1000.0,
+                    "solve_ms": solved.elapsed.as_secs_f64() * 1000.0,
+                }),
+            }
+        }
+        Ok(Err(err)) => {
+            let mut body = json!({ "day": err.day, "error": err.message });
+            if let Some(location) = err.location {
+                body["line"] = location.line.into();
+                body["column"] = location.column.into();
+            }
+            Response { status: 400, body }
+        }
+        Err(_) => Response::error(500, format!("the day {day} solver crashed")),
+    }
+}
+
+fn respond(mut request: Request) {
+    let mut body = vec![];
+    let read = request
+        .as_reader()
+        .take(MAX_INPUT + 1)
+        .read_to_end(&mut body);
+    let response = match read {
+        Ok(_) => {
+            let body = (body.len() as u64 <= MAX_INPUT).then_some(&body[..]);
+            route(request.method(), request.url(), body)
+        }
+        Err(err) => Response::error(400, format!("cannot read the request body: {err}")),
+    };
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    let reply = tiny_http::Response::from_string(response.body.to_string())
+        .with_status_code(response.status)
+        .with_header(content_type);
+    // The client hanging up early is no reason to stop serving others.
+    let _ = request.respond(reply);
+}
+
+/// Answers requests one at a time until the server is unblocked.
+pub fn serve(server: &Server) {
+    for request in server.incoming_requests() {
+        respond(request);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"1000\n2000\n\n4000\n";
+
+    #[test]
+    fn solves_posted_inputs() {
+        let response = route(&Method::Post, "/day/1/part/2", Some(INPUT));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["day"], 1);
+        assert_eq!(response.body["part"], 2);
+        assert_eq!(response.body["answer"], "7000");
+        assert!(response.body["solve_ms"].is_f64());
+    }
+
+    #[test]
+    fn parse_errors_carry_their_location() {
+        let response = route(&Method::Post, "/day/1/part/1", Some(b"1000\n\nabc\n"));
+        assert_eq!(response.status, 400);
+        assert_eq!(response.body["day"], 1);
+        assert_eq!((response.body["line"].as_u64(), response.body["column"].as_u64()), (Some(3), Some(1)));
+    }
+
+    #[test]
+    fn rejects_other_requests() {
+        let status = |method, url, body| route(&method, url, body).status;
+        assert_eq!(status(Method::Post, "/day/14/part/1", Some(INPUT)), 404);
+        assert_eq!(status(Method::Post, "/day/1/part/3", Some(INPUT)), 404);
+        assert_eq!(status(Method::Post, "/days", Some(INPUT)), 404);
+        assert_eq!(status(Method::Get, "/day/1/part/1", Some(INPUT)), 405);
+        assert_eq!(status(Method::Post, "/day/1/part/1", None), 413);
+        assert_eq!(status(Method::Post, "/day/1/part/1", Some(b"\xff")), 400);
+    }
+
+    #[test]
+    fn panicking_solvers_get_a_500() {
+        let response = answer(1, 1, || panic!("the solver broke"));
+        assert_eq!(response.status, 500);
+        assert_eq!(response.body["error"], "the day 1 solver crashed");
+    }
+}
@@ -0,0 +1,27 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use tiny_http::Server;
+
+/// Serve the solvers over HTTP on localhost
+#[derive(Parser)]
+#[command(name = "aoc-server")]
+struct Cli {
+    /// Port to listen on, any free one when 0
+    #[arg(long, default_value_t = 8022)]
+    port: u16,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let server = match Server::http(("127.0.0.1", cli.port)) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("cannot listen on port {}: {err}", cli.port);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on http://{}", server.server_addr());
+    aoc_server::serve(&server);
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,54 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    sync::Arc,
+    thread,
+};
+
+use serde_json::Value;
+use tiny_http::Server;
+
+/// Sends one request over a fresh connection and returns the status code
+/// and the JSON body.
+fn request(port: u16, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    assert!(head.contains("Content-Type: application/json"), "{head}");
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn answers_over_http() {
+    let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+    let port = server.server_addr().to_ip().unwrap().port();
+    let serving = thread::spawn({
+        let server = Arc::clone(&server);
+        move || aoc_server::serve(&server)
+    });
+
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../day6/input.txt")).unwrap();
+    let (status, body) = request(port, "POST", "/day/6/part/2", &input);
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], "3120");
+
+    let (status, body) = request(port, "POST", "/day/9/part/1", "R 4\nX 2\n");
+    assert_eq!(status, 400);
+    assert_eq!((body["line"].as_u64(), body["column"].as_u64()), (Some(2), Some(1)));
+    assert_eq!(body["error"], "unknown direction \"X\"");
+
+    let (status, _) = request(port, "GET", "/day/6/part/2", "");
+    assert_eq!(status, 405);
+
+    server.unblock();
+    serving.join().unwrap();
+}
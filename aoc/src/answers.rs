@@ -0,0 +1,129 @@
+use std::{fmt, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::days;
+
+/// One expected answer from `answers.toml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    pub expected: String,
+}
+
+#[derive(Deserialize)]
+struct AnswerFile {
+    answer: Vec<Answer>,
+}
+
+pub fn parse(text: &str) -> Result<Vec<Answer>, toml::de::Error> {
+    toml::from_str::<AnswerFile>(text).map(|file| file.answer)
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Match,
+    Mismatch { actual: String },
+    /// The input could not be read or the solver returned an error.
+    Failed(String),
+}
+
+/// The result of checking one [`Answer`].
+#[derive(Debug)]
+pub struct Check {
+    pub answer: Answer,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Match)
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Answer { day, part, expected } = &self.answer;
+        write!(f, "day {day} part {part}: ")?;
+        match &self.outcome {
+            Outcome::Match => write!(f, "ok"),
+            Outcome::Mismatch { actual } => {
+                write!(f, "expected {:?}, got {:?}", expected.trim_end(), actual.trim_end())
+            }
+            Outcome::Failed(err) => write!(f, "failed: {err}"),
+        }
+    }
+}
+
+/// Runs every answer's solver on `{inputs}/dayN/input.txt`, parsing each
+/// input once. Answers are compared ignoring trailing whitespace.
+pub fn verify(answers: &[Answer], inputs: &Path) -> Vec<Check> {
+    let mut checks = vec![];
+    for day in days::DAYS {
+        let answers: Vec<&Answer> = answers
+            .iter()
+            .filter(|a| a.day == day.day && matches!(a.part, 1 | 2))
+            .collect();
+        if answers.is_empty() {
+            continue;
+        }
+        let parts: Vec<u8> = answers.iter().map(|a| a.part).collect();
+
+        let solved = fs::read_to_string(days::input_path(inputs, day.day))
+            .map_err(|err| format!("cannot read input: {err}"))
+            .and_then(|text| (day.solve)(&text, &parts).map_err(|err| err.to_string()));
+
+        for (i, answer) in answers.into_iter().enumerate() {
+            let outcome = match &solved {
+                Ok(report) => {
+                    let actual = &report.parts[i].answer;
+                    if actual.trim_end() == answer.expected.trim_end() {
+                        Outcome::Match
+                    } else {
+                        Outcome::Mismatch { actual: actual.clone() }
+                    }
+                }
+                Err(err) => Outcome::Failed(err.clone()),
+            };
+            checks.push(Check { answer: answer.clone(), outcome });
+        }
+    }
+
+    for answer in answers {
+        let problem = if days::get(answer.day).is_none() {
+            "no solver for this day"
+        } else if !matches!(answer.part, 1 | 2) {
+            "there are only parts 1 and 2"
+        } else {
+            continue;
+        };
+        checks.push(Check {
+            answer: answer.clone(),
+            outcome: Outcome::Failed(problem.to_string()),
+        });
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_answers() {
+        let answers = parse("[[answer]]\nday = 1\npart = 2\nexpected = \"45000\"\n").unwrap();
+        assert_eq!(answers.len(), 1);
+        assert_eq!((answers[0].day, answers[0].part), (1, 2));
+        assert_eq!(answers[0].expected, "45000");
+    }
+
+    #[test]
+    fn reports_unreadable_inputs() {
+        let answers = parse("[[answer]]\nday = 1\npart = 1\nexpected = \"1\"\n").unwrap();
+        let checks = verify(&answers, Path::new("/nonexistent"));
+        assert_eq!(checks.len(), 1);
+        assert!(!checks[0].passed());
+        assert!(checks[0].to_string().starts_with("day 1 part 1: failed: cannot read input"));
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use aoc_common::{normalize, Error, Frame, Solution, Visualize};
+use tracing::info_span;
+
+pub struct PartReport {
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Timings for one day: parsing once, then solving each requested part.
+pub struct Report {
+    pub parse: Duration,
+    pub parts: Vec<PartReport>,
+}
+
+pub type Solver = fn(&str, &[u8]) -> Result<Report, Error>;
+
+/// Runs one part of a day's simulation, handing every frame to the callback.
+pub type Animator = fn(&str, u8, &mut dyn FnMut(Frame)) -> Result<(), Error>;
+
+pub struct Day {
+    pub day: u8,
+    pub solve: Solver,
+    /// Only the days whose answer comes out of a simulation have one.
+    pub visualize: Option<Animator>,
+}
+
+fn solve<S: Solution>(input: &str, parts: &[u8]) -> Result<Report, Error> {
+    let input = normalize(input);
+    let start = Instant::now();
+    let parsed = info_span!("parse", day = S::DAY, bytes = input.len()).in_scope(|| S::parse(&input))?;
+    let parse = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let _span = info_span!("solve", day = S::DAY, part).entered();
+            let start = Instant::now();
+            let answer = match part {
+                1 => S::part1(&parsed)?,
+                _ => S::part2(&parsed)?,
+            };
+            Ok(PartReport {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(Report { parse, parts })
+}
+
+fn animate<S: Visualize>(input: &str, part: u8, frame: &mut dyn FnMut(Frame)) -> Result<(), Error> {
+    let input = normalize(input);
+    let parsed = S::parse(&input)?;
+    S::frames(&parsed, part, frame)
+}
+
+const fn day<S: Solution>() -> Day {
+    Day {
+        day: S::DAY,
+        solve: solve::<S>,
+        visualize: None,
+    }
+}
+
+const fn animated<S: Visualize>() -> Day {
+    Day {
+        visualize: Some(animate::<S>),
+        ..day::<S>()
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    day::<day1::Day1>(),
+    day::<day2::Day2>(),
+    day::<day3::Day3>(),
+    day::<day4::Day4>(),
+    animated::<day5::Day5>(),
+    day::<day6::Day6>(),
+    day::<day7::Day7>(),
+    day::<day8::Day8>(),
+    animated::<day9::Day9>(),
+    animated::<day10::Day10>(),
+    animated::<day11::Day11>(),
+    animated::<day12::Day12>(),
+    day::<day13::Day13>(),
+];
+
+pub fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/// Where a day's puzzle input lives below the `--inputs` directory.
+pub fn input_path(inputs: &Path, day: u8) -> PathBuf {
+    inputs.join(format!("day{day}")).join("input.txt")
+}
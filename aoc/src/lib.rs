@@ -0,0 +1,5 @@
+pub mod answers;
+pub mod days;
+pub mod visualize;
+
+pub use days::{Day, DAYS};
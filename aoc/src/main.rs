@@ -0,0 +1,381 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use aoc::{
+    answers, days,
+    visualize::{Gif, Terminal},
+    DAYS,
+};
+use aoc_common::{load_input, normalize, Frame};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{prelude::*, EnvFilter};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one day (or every day) and print a summary table
+    Run {
+        /// Day to run, all days when omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=13))]
+        day: Option<u8>,
+        /// Part to run, both parts when omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Puzzle input file, or `-` for stdin
+        #[arg(long, requires = "day")]
+        input: Option<String>,
+        /// Directory holding the `dayN/input.txt` files
+        #[arg(long, default_value = ".")]
+        inputs: PathBuf,
+        /// How to print the results
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Record every span to this Chrome trace file, for chrome://tracing
+        /// or Perfetto
+        #[arg(long)]
+        trace: Option<PathBuf>,
+    },
+    /// Check every solver against the expected answers for its real input
+    Verify {
+        /// File listing the expected answers
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+        /// Directory holding the `dayN/input.txt` files
+        #[arg(long, default_value = ".")]
+        inputs: PathBuf,
+    },
+    /// Watch a day's simulation in the terminal, or save it as a GIF
+    Visualize {
+        /// Day to watch; days 5, 9, 10, 11 and 12 are simulations
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=13))]
+        day: u8,
+        /// Part to watch
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Puzzle input file, or `-` for stdin
+        #[arg(long)]
+        input: Option<String>,
+        /// Directory holding the `dayN/input.txt` files
+        #[arg(long, default_value = ".")]
+        inputs: PathBuf,
+        /// Frames per second
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        fps: u32,
+        /// Only show every Nth frame, plus the last one
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        every: u64,
+        /// Write an animated GIF here instead of playing in the terminal
+        #[arg(long)]
+        gif: Option<PathBuf>,
+        /// Pixels per cell in the GIF
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..=64))]
+        scale: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// A table for people
+    Text,
+    /// One JSON object per line and part
+    Json,
+}
+
+struct Row {
+    day: u8,
+    part: u8,
+    answer: String,
+    parse: Duration,
+    solve: Duration,
+}
+
+/// Reads the input for `day` and [`normalize`]s it, so errors render
+/// against the text the solver located them in.
+fn read_input(input: Option<&str>, inputs: &Path, day: u8) -> io::Result<String> {
+    match input {
+        Some("-") => {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            Ok(normalize(&buffer).into_owned())
+        }
+        Some(path) => load_input(path),
+        None => load_input(days::input_path(inputs, day)),
+    }
+}
+
+fn print_table(rows: &[Row]) {
+    println!("{:>3}  {:>4}  {:>12}  {:>12}  Answer", "Day", "Part", "Parse", "Solve");
+    for Row { day, part, answer, parse, solve } in rows {
+        let mut lines = answer.lines();
+        let first = lines.next().unwrap_or_default();
+        println!(
+            "{day:>3}  {part:>4}  {:>12}  {:>12}  {first}",
+            format!("{parse:.2?}"),
+            format!("{solve:.2?}"),
+        );
+        for line in lines {
+            println!("{:>3}  {:>4}  {:>12}  {:>12}  {line}", "", "", "", "");
+        }
+    }
+    let total: Duration = rows.iter().map(|row| row.parse + row.solve).sum();
+    println!("{:>3}  {:>4}  {:>12}  {:>12}", "", "", "", format!("{total:.2?}"));
+}
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    day: u8,
+    part: u8,
+    answer: &'a str,
+    parse_ms: f64,
+    solve_ms: f64,
+}
+
+fn print_json(rows: &[Row]) {
+    for row in rows {
+        let json = JsonRow {
+            day: row.day,
+            part: row.part,
+            answer: &row.answer,
+            parse_ms: row.parse.as_secs_f64() * 1000.0,
+            solve_ms: row.solve.as_secs_f64() * 1000.0,
+        };
+        println!("{}", serde_json::to_string(&json).expect("rows serialize"));
+    }
+}
+
+fn run(
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<&str>,
+    inputs: &Path,
+    format: Format,
+) -> ExitCode {
+    let selected = DAYS
+        .iter()
+        .filter(|d| day.is_none_or(|day| d.day == day));
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let mut rows = vec![];
+    for solution in selected {
+        let text = match read_input(input, inputs, solution.day) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("day {}: cannot read input: {err}", solution.day);
+                return ExitCode::FAILURE;
+            }
+        };
+        let report = match (solution.solve)(&text, &parts) {
+            Ok(report) => report,
+            Err(err) => {
+                eprintln!("{}", err.render(&text));
+                return ExitCode::FAILURE;
+            }
+        };
+        for part in report.parts {
+            rows.push(Row {
+                day: solution.day,
+                part: part.part,
+                answer: part.answer,
+                parse: report.parse,
+                solve: part.elapsed,
+            });
+        }
+    }
+
+    match format {
+        Format::Text => print_table(&rows),
+        Format::Json => print_json(&rows),
+    }
+    ExitCode::SUCCESS
+}
+
+fn verify(answers: &Path, inputs: &Path) -> ExitCode {
+    let expected = match fs::read_to_string(answers) {
+        Ok(text) => answers::parse(&text).map_err(|err| err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+    let expected = match expected {
+        Ok(expected) => expected,
+        Err(err) => {
+            eprintln!("cannot load {}: {err}", answers.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let checks = answers::verify(&expected, inputs);
+    for check in &checks {
+        println!("{check}");
+    }
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    println!("{} answers checked, {failed} failed", checks.len());
+
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Where `aoc visualize` sends its frames.
+enum Sink {
+    Terminal(Terminal<io::Stdout>),
+    /// The GIF is only created once the first frame gives its size.
+    Gif {
+        path: PathBuf,
+        scale: usize,
+        fps: u32,
+        gif: Option<Gif<BufWriter<File>>>,
+    },
+}
+
+impl Sink {
+    fn show(&mut self, frame: &Frame) -> Result<(), String> {
+        match self {
+            Sink::Terminal(terminal) => terminal.show(frame).map_err(|err| err.to_string()),
+            Sink::Gif { path, scale, fps, gif } => {
+                let gif = match gif {
+                    Some(gif) => gif,
+                    None => {
+                        let file = File::create(&*path).map_err(|err| err.to_string())?;
+                        let writer = Gif::new(BufWriter::new(file), frame, *scale, *fps);
+                        gif.insert(writer.map_err(|err| err.to_string())?)
+                    }
+                };
+                gif.write(frame).map_err(|err| err.to_string())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        if let Sink::Gif { gif: Some(gif), .. } = self {
+            let mut writer = gif.finish().map_err(|err| err.to_string())?;
+            writer.flush().map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+struct Options {
+    fps: u32,
+    every: u64,
+    gif: Option<PathBuf>,
+    scale: usize,
+}
+
+fn visualize(day: u8, part: u8, input: Option<&str>, inputs: &Path, options: Options) -> ExitCode {
+    let Some(animate) = days::get(day).and_then(|d| d.visualize) else {
+        eprintln!("day {day} has no visualization");
+        return ExitCode::FAILURE;
+    };
+    let text = match read_input(input, inputs, day) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("day {day}: cannot read input: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Options { fps, every, gif, scale } = options;
+    let mut sink = match gif {
+        Some(path) => Sink::Gif { path, scale, fps, gif: None },
+        None => Sink::Terminal(Terminal::new(io::stdout(), fps)),
+    };
+    // Frames are shown one behind, so the last one is always shown even
+    // when `every` skips it.
+    let mut count = 0;
+    let mut pending: Option<Frame> = None;
+    let mut failure: Option<String> = None;
+    let solved = animate(&text, part, &mut |frame| {
+        if failure.is_some() {
+            return;
+        }
+        if let Some(previous) = pending.take() {
+            if count % every == 0 {
+                failure = sink.show(&previous).err();
+            }
+            count += 1;
+        }
+        pending = Some(frame);
+    });
+
+    if let Err(err) = solved {
+        eprintln!("{}", err.render(&text));
+        return ExitCode::FAILURE;
+    }
+    let shown = match (failure, pending) {
+        (Some(err), _) => Err(err),
+        (None, Some(last)) => sink.show(&last).and_then(|()| sink.finish()),
+        (None, None) => sink.finish(),
+    };
+    match shown {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("cannot show the frames: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Solver diagnostics go to stderr, filtered with `RUST_LOG`, so stdout
+/// only ever holds the results. With a `trace` file every span and event
+/// is recorded there too, whatever `RUST_LOG` says; the trace is complete
+/// once the returned guard is dropped.
+fn init_tracing(trace: Option<&Path>) -> io::Result<Option<FlushGuard>> {
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(io::stderr)
+        .with_filter(EnvFilter::from_default_env());
+    let (chrome, guard) = match trace {
+        Some(path) => {
+            let (layer, guard) = ChromeLayerBuilder::new()
+                .writer(File::create(path)?)
+                .include_args(true)
+                .build();
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+    tracing_subscriber::registry().with(stderr).with(chrome).init();
+    Ok(guard)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let trace = match &cli.command {
+        Command::Run { trace, .. } => trace.as_deref(),
+        _ => None,
+    };
+    let _trace = match init_tracing(trace) {
+        Ok(guard) => guard,
+        Err(err) => {
+            eprintln!("cannot create the trace file: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match cli.command {
+        Command::Run { day, part, input, inputs, format, .. } => {
+            run(day, part, input.as_deref(), &inputs, format)
+        }
+        Command::Verify { answers, inputs } => verify(&answers, &inputs),
+        Command::Visualize { day, part, input, inputs, fps, every, gif, scale } => {
+            let options = Options { fps, every, gif, scale: scale as usize };
+            visualize(day, part, input.as_deref(), &inputs, options)
+        }
+    }
+}
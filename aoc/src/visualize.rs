@@ -0,0 +1,156 @@
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use aoc_common::{Frame, Point};
+use gif::{EncodingError, Encoder, Repeat};
+
+/// Plays frames in a terminal, redrawing the screen in place.
+pub struct Terminal<W> {
+    out: W,
+    delay: Duration,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(out: W, fps: u32) -> Self {
+        Terminal {
+            out,
+            delay: Duration::from_secs(1) / fps.max(1),
+        }
+    }
+
+    pub fn show(&mut self, frame: &Frame) -> io::Result<()> {
+        // Home the cursor and clear the screen before drawing.
+        write!(self.out, "\x1b[H\x1b[2J{}\n{}", frame.caption, frame.cells)?;
+        self.out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+/// The colour of every cell character: an index into [`palette`].
+fn colour(c: char) -> u8 {
+    match c {
+        ' ' => 0,
+        '.' => 1,
+        '#' => 2,
+        '@' => 3,
+        '=' => 4,
+        '[' | ']' => 5,
+        '0'..='9' => 6 + (c as u8 - b'0'),
+        'a'..='z' => 16 + (c as u8 - b'a'),
+        'A'..='Z' => 42 + (c as u8 - b'A'),
+        _ => 68,
+    }
+}
+
+/// `[r, g, b, ...]` for every index [`colour`] hands out.
+fn palette() -> Vec<u8> {
+    let mut palette = vec![
+        0x10, 0x10, 0x18, // ' '
+        0x40, 0x40, 0x50, // '.'
+        0xf0, 0xf0, 0xf0, // '#'
+        0xff, 0xd0, 0x20, // '@'
+        0x30, 0xc0, 0x60, // '='
+        0x80, 0x80, 0x80, // '[' and ']'
+    ];
+    // Knots and other digits: red fading to orange.
+    for i in 0..10u8 {
+        palette.extend([0xff, 0x30 + i * 0x10, 0x30]);
+    }
+    // Heights: dark green at `a` up to pale brown at `z`.
+    for i in 0..26u8 {
+        palette.extend([0x20 + i * 7, 0x60 + i * 5, 0x20 + i * 3]);
+    }
+    // Letters marking something, like `S` and `E`: spread around the hues.
+    for i in 0..26u8 {
+        let step = i.wrapping_mul(97);
+        palette.extend([step, step.wrapping_add(85), step.wrapping_add(170)]);
+    }
+    palette.extend([0xc0, 0xc0, 0xc0]);
+    palette
+}
+
+/// Writes frames to an animated GIF that loops forever, `scale` pixels per
+/// cell. The GIF takes its size from the first frame; later frames are
+/// cropped or padded with blank cells to fit. Captions are not drawn.
+pub struct Gif<W: Write> {
+    encoder: Encoder<W>,
+    width: usize,
+    height: usize,
+    scale: usize,
+    delay: u16,
+}
+
+impl<W: Write> Gif<W> {
+    pub fn new(out: W, first: &Frame, scale: usize, fps: u32) -> Result<Self, EncodingError> {
+        let (width, height) = (first.cells.width(), first.cells.height());
+        let size = |cells: usize| {
+            u16::try_from(cells * scale).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "the frames are too large for a GIF")
+            })
+        };
+        let mut encoder = Encoder::new(out, size(width)?, size(height)?, &palette())?;
+        encoder.set_repeat(Repeat::Infinite)?;
+        Ok(Gif {
+            encoder,
+            width,
+            height,
+            scale,
+            // GIF delays are in hundredths of a second.
+            delay: (100 / fps.max(1)).max(1) as u16,
+        })
+    }
+
+    pub fn write(&mut self, frame: &Frame) -> Result<(), EncodingError> {
+        let scale = self.scale;
+        let (width, height) = (self.width * scale, self.height * scale);
+        let pixels: Vec<u8> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x / scale, y / scale)))
+            .map(|(x, y)| {
+                let cell = frame.cells.get(Point::new(x as i64, y as i64));
+                colour(cell.copied().unwrap_or(' '))
+            })
+            .collect();
+        let mut gif = gif::Frame::from_indexed_pixels(width as u16, height as u16, pixels, None);
+        gif.delay = self.delay;
+        self.encoder.write_frame(&gif)
+    }
+
+    pub fn finish(self) -> Result<W, EncodingError> {
+        self.encoder.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(lines: &[&str]) -> Frame {
+        Frame::from_lines("", lines[0].len(), lines)
+    }
+
+    #[test]
+    fn every_colour_is_in_the_palette() {
+        let colours = palette().len() / 3;
+        for c in (' '..='~').chain(['\u{e9}']) {
+            assert!(usize::from(colour(c)) < colours, "{c:?}");
+        }
+        assert!(colours <= 256);
+    }
+
+    #[test]
+    fn writes_an_animated_gif() {
+        let first = frame(&["S.#", "abE"]);
+        let mut gif = Gif::new(vec![], &first, 2, 10).unwrap();
+        gif.write(&first).unwrap();
+        gif.write(&frame(&["@@@@", "@@@@", "@@@@"])).unwrap();
+        let bytes = gif.finish().unwrap();
+
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert_eq!(&bytes[6..10], [6, 0, 4, 0]);
+        assert_eq!(bytes.last(), Some(&0x3b));
+    }
+}
@@ -0,0 +1,27 @@
+use std::{fs, path::Path};
+
+use aoc::{answers, DAYS};
+
+#[test]
+fn every_answer_matches_its_input() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let text = fs::read_to_string(root.join("answers.toml")).unwrap();
+    let expected = answers::parse(&text).unwrap();
+
+    for day in DAYS {
+        for part in [1, 2] {
+            assert!(
+                expected.iter().any(|a| a.day == day.day && a.part == part),
+                "answers.toml has no answer for day {} part {part}",
+                day.day
+            );
+        }
+    }
+
+    let failures: Vec<String> = answers::verify(&expected, &root)
+        .iter()
+        .filter(|check| !check.passed())
+        .map(|check| check.to_string())
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,90 @@
+use std::process::Command;
+
+use serde_json::Value;
+
+#[test]
+fn json_format_prints_one_object_per_part() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--day", "13", "--format", "json", "--inputs"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let rows: Vec<Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 2);
+    for (row, part) in rows.iter().zip(1..) {
+        assert_eq!(row["day"], 13);
+        assert_eq!(row["part"], part);
+        assert!(row["answer"].is_string());
+        assert!(row["parse_ms"].is_f64() && row["solve_ms"].is_f64());
+    }
+}
+
+#[test]
+fn trace_records_solver_spans() {
+    let trace = std::env::temp_dir().join(format!("aoc-trace-{}.json", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--day", "12", "--part", "1", "--inputs"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+        .arg("--trace")
+        .arg(&trace)
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stderr.is_empty());
+
+    let events: Vec<Value> = serde_json::from_slice(&std::fs::read(&trace).unwrap()).unwrap();
+    std::fs::remove_file(&trace).unwrap();
+    for span in ["parse", "build_graph", "solve", "shortest_path"] {
+        assert!(events.iter().any(|event| event["name"] == span), "no {span} span");
+    }
+}
+
+#[test]
+fn visualize_writes_a_gif() {
+    let gif = std::env::temp_dir().join(format!("aoc-day10-{}.gif", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["visualize", "--day", "10", "--every", "40", "--scale", "1", "--inputs"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+        .arg("--gif")
+        .arg(&gif)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let bytes = std::fs::read(&gif).unwrap();
+    std::fs::remove_file(&gif).unwrap();
+    assert!(bytes.starts_with(b"GIF89a"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["visualize", "--day", "1"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "day 1 has no visualization\n");
+}
+
+#[test]
+fn errors_point_into_the_normalized_input() {
+    let input = std::env::temp_dir().join(format!("aoc-day4-{}.txt", std::process::id()));
+    std::fs::write(&input, "\u{feff}2-4,6-x\r\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--day", "4", "--input"])
+        .arg(&input)
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    std::fs::remove_file(&input).unwrap();
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 | 2-4,6-x\n  |       ^"), "{stderr}");
+}
@@ -1,7 +1,20 @@
 use day1::process_part1;
-use std::fs;
+use aoc_common::load_input;
+use std::process::ExitCode;
 
-fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part1(&file));
+fn main() -> ExitCode {
+    let result = load_input("./input.txt")
+        .map_err(|err| format!("cannot read ./input.txt: {err}"))
+        .and_then(|file| process_part1(&file).map_err(|err| err.render(&file)));
+
+    match result {
+        Ok(answer) => {
+            println!("{answer}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
 }
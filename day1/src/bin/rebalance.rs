@@ -0,0 +1,34 @@
+use day1::rebalance;
+use aoc_common::load_input;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+/// Prints the transfers that even out the loads in ./input.txt.
+fn main() -> ExitCode {
+    let result = load_input("./input.txt")
+        .map_err(|err| format!("cannot read ./input.txt: {err}"))
+        .and_then(|file| rebalance(&file).map_err(|err| err.render(&file)));
+
+    match result {
+        Ok(plan) => exit(write!(io::stdout().lock(), "{plan}")),
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Exits cleanly once the output is written, or once whatever reads it
+/// stops reading, as `head` does.
+fn exit(written: io::Result<()>) -> ExitCode {
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("cannot write the output: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
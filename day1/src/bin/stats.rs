@@ -0,0 +1,36 @@
+use day1::report;
+use aoc_common::load_input;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+/// Prints statistics over the loads in ./input.txt, as CSV with `--csv`.
+fn main() -> ExitCode {
+    let csv = std::env::args().skip(1).any(|arg| arg == "--csv");
+    let result = load_input("./input.txt")
+        .map_err(|err| format!("cannot read ./input.txt: {err}"))
+        .and_then(|file| report(&file).map_err(|err| err.render(&file)));
+
+    match result {
+        Ok(report) if csv => exit(write!(io::stdout().lock(), "{}", report.to_csv())),
+        Ok(report) => exit(write!(io::stdout().lock(), "{report}")),
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Exits cleanly once the output is written, or once whatever reads it
+/// stops reading, as `head` does.
+fn exit(written: io::Result<()>) -> ExitCode {
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("cannot write the output: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
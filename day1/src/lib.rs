@@ -1,52 +1,200 @@
-pub fn process_part1(input: &str) -> String {
-    let result = input
-        .split("\n\n")
-        .map(|elf_load| {
-            elf_load
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .max()
-        .unwrap();
-    result.to_string()
-}
-
-pub fn process_part3(input: &str) -> String {
-    let mut result = input
-        .split("\n\n")
-        .map(|elf_load| {
-            elf_load
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
-        })
-        .collect::<Vec<_>>();
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead};
+
+use aoc_common::{for_each_line, normalize, Error, Solution};
+
+mod parallel;
+mod rebalance;
+mod stats;
+
+pub use parallel::{parallel_part1, parallel_part2, parallel_top_k};
+pub use rebalance::{plan, rebalance, Method, Plan, Transfer, EXACT_ITEMS};
+pub use stats::{report, Bucket, Report, PERCENTILES};
+
+/// Names elf `index` as carrying more calories than fit a `u64`.
+fn overflow(index: usize) -> Error {
+    Error::new(Day1::DAY, format!("the calories elf {index} carries overflow a u64"))
+}
+
+/// Adds an item to the load of elf `index`.
+fn add(index: usize, total: u64, calories: u64) -> Result<u64, Error> {
+    total.checked_add(calories).ok_or_else(|| overflow(index))
+}
+
+/// The calories elf `index` carries.
+fn total(index: usize, elf_load: &[u64]) -> Result<u64, Error> {
+    elf_load
+        .iter()
+        .try_fold(0, |sum, &calories| add(index, sum, calories))
+}
 
-    result.sort_by(|a, b| b.cmp(a));
-    let sum: u32 = result.iter().take(3).sum();
-    sum.to_string()
+/// The calories of one item, without a location for a bad one.
+fn calories(item: &str) -> Result<u64, Error> {
+    item.parse::<u64>()
+        .map_err(|err| Error::new(Day1::DAY, format!("invalid calories {item:?}: {err}")))
 }
 
-pub fn process_part2(input: &str) -> String {
-    let mut result = input
-        .split("\n\n")
-        .map(|elf_load| {
-            elf_load
-                .lines()
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>()
+/// Each elf's items in `text`. Every blank line ends an elf, as it does
+/// when the input is streamed, so a run of them leaves elves carrying
+/// nothing.
+fn loads(text: &str) -> Vec<Vec<&str>> {
+    let mut loads = vec![vec![]];
+    for item in text.lines() {
+        match loads.last_mut() {
+            Some(load) if !item.is_empty() => load.push(item),
+            _ => loads.push(vec![]),
+        }
+    }
+    loads
+}
+
+/// One elf's load: who carries it, how many items and how many calories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elf {
+    /// 1-based, in the order the elves appear in the input.
+    pub index: usize,
+    pub items: usize,
+    pub total: u64,
+}
+
+impl Elf {
+    fn new(index: usize, elf_load: &[u64]) -> Result<Self, Error> {
+        Ok(Elf {
+            index,
+            items: elf_load.len(),
+            total: total(index, elf_load)?,
         })
-        .collect::<Vec<_>>();
+    }
+}
+
+/// The `k` heaviest elves pushed so far, kept in a heap of at most `k`
+/// elves rather than sorting them all. Of two elves carrying as much, the
+/// one listed first counts as heavier.
+struct Heaviest {
+    k: usize,
+    // The lightest kept elf sits on top, ready to be pushed out.
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>, usize)>>,
+}
+
+impl Heaviest {
+    fn new(k: usize) -> Self {
+        Heaviest {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn push(&mut self, Elf { index, items, total }: Elf) {
+        self.heap.push(Reverse((total, Reverse(index), items)));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    /// The kept elves, heaviest first.
+    fn into_vec(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index), items))| Elf { index, items, total })
+            .collect()
+    }
+
+    /// The calories the kept elves carry together, which cannot overflow
+    /// for any `k` that fits in memory.
+    fn into_total(self) -> u128 {
+        self.heap.iter().map(|Reverse((total, ..))| u128::from(*total)).sum()
+    }
+}
+
+/// Calls `elf` with each elf in `reader` as it is read, one line at a
+/// time.
+fn stream_elves(reader: impl BufRead, mut elf: impl FnMut(Elf)) -> Result<(), Error> {
+    let mut load = Elf { index: 1, items: 0, total: 0 };
+    for_each_line(Day1::DAY, reader, |number, item| {
+        if item.is_empty() {
+            elf(load);
+            load = Elf { index: load.index + 1, items: 0, total: 0 };
+            return Ok(());
+        }
+        let calories = calories(item).map_err(|err| err.at(number, 1))?;
+        load.total = add(load.index, load.total, calories)?;
+        load.items += 1;
+        Ok(())
+    })?;
+    elf(load);
+    Ok(())
+}
+
+/// The `k` heaviest of the parsed elves.
+fn heaviest(parsed: &[Vec<u64>], k: usize) -> Result<Heaviest, Error> {
+    let mut heaviest = Heaviest::new(k);
+    for (i, elf_load) in parsed.iter().enumerate() {
+        heaviest.push(Elf::new(i + 1, elf_load)?);
+    }
+    Ok(heaviest)
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Parsed<'a> = Vec<Vec<u64>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        loads(input)
+            .into_iter()
+            .map(|elf_load| {
+                elf_load
+                    .into_iter()
+                    .map(|item| calories(item).map_err(|err| err.at_fragment(input, item)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(heaviest(parsed, 1)?.into_total().to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(heaviest(parsed, 3)?.into_total().to_string())
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day1::part1(&Day1::parse(&normalize(input))?)
+}
 
-    result.sort_by(|a, b| b.cmp(a));
-    let sum: u32 = result.iter().take(3).sum();
-    sum.to_string()
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day1::part2(&Day1::parse(&normalize(input))?)
+}
+
+/// The `k` elves carrying the most calories, heaviest first. Elves
+/// carrying as much are listed in input order.
+pub fn top_k(input: &str, k: usize) -> Result<Vec<Elf>, Error> {
+    Ok(heaviest(&Day1::parse(&normalize(input))?, k)?.into_vec())
+}
+
+/// [`process_part1`] for an input read one line at a time.
+pub fn stream_part1(input: impl BufRead) -> Result<String, Error> {
+    let mut heaviest = Heaviest::new(1);
+    stream_elves(input, |elf| heaviest.push(elf))?;
+    Ok(heaviest.into_total().to_string())
+}
+
+/// [`process_part2`] for an input read one line at a time.
+pub fn stream_part2(input: impl BufRead) -> Result<String, Error> {
+    let mut heaviest = Heaviest::new(3);
+    stream_elves(input, |elf| heaviest.push(elf))?;
+    Ok(heaviest.into_total().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::Location;
+    use proptest::prelude::*;
 
     const INPUT: &str = "1000
 2000
@@ -65,13 +213,138 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "24000");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "45000");
     }
+
+    #[test]
+    fn invalid_calories_are_located() {
+        let err = process_part1("1000\n\n20x0\n").unwrap_err();
+        assert_eq!(err.location, Some(Location { line: 3, column: 1 }));
+    }
+
+    #[test]
+    fn top_k_names_the_elves() {
+        let elf = |index, items, total| Elf { index, items, total };
+        assert_eq!(
+            top_k(INPUT, 3).unwrap(),
+            [elf(4, 3, 24000), elf(3, 2, 11000), elf(5, 1, 10000)]
+        );
+        assert_eq!(top_k(INPUT, 0).unwrap(), []);
+        assert_eq!(top_k(INPUT, 100).unwrap().len(), 5);
+        assert_eq!(top_k("5\n\n3\n2\n\n5", 2).unwrap(), [elf(1, 1, 5), elf(2, 2, 5)]);
+    }
+
+    #[test]
+    fn top_k_takes_any_k() {
+        let elf = Elf { index: 1, items: 1, total: 0 };
+        assert_eq!(top_k("0", 0).unwrap(), []);
+        assert_eq!(top_k("0", usize::MAX).unwrap(), [elf]);
+    }
+
+    #[test]
+    fn totals_go_beyond_u32() {
+        let input = "4294967295\n1\n\n5000000000\n\n18446744073709551615";
+        assert_eq!(process_part1(input).unwrap(), "18446744073709551615");
+        assert_eq!(process_part2(input).unwrap(), "18446744083004518911");
+        assert_eq!(top_k(input, 3).unwrap()[2].total, 4294967296);
+    }
+
+    #[test]
+    fn overflow_names_the_elf() {
+        let err = process_part1("1\n\n18446744073709551615\n1").unwrap_err();
+        assert_eq!(err.to_string(), "day 1: the calories elf 2 carries overflow a u64");
+    }
+
+    #[test]
+    fn windows_line_endings_work() {
+        let input = format!("\u{feff}{}\r\n\r\n", INPUT.replace('\n', "\r\n"));
+        assert_eq!(process_part2(&input).unwrap(), "45000");
+        assert_eq!(stream_part2(input.as_bytes()).unwrap(), "45000");
+    }
+
+    #[test]
+    fn streaming_matches_the_whole_input() {
+        let inputs = [
+            INPUT,
+            "",
+            "1\n\n\n2",
+            "1\n\n",
+            "1000\n\n20x0\n",
+            "1\n\n18446744073709551615\n1",
+        ];
+        for input in inputs {
+            assert_eq!(stream_part1(input.as_bytes()), process_part1(input), "{input:?}");
+            assert_eq!(stream_part2(input.as_bytes()), process_part2(input), "{input:?}");
+        }
+    }
+
+    /// Every elf's items, a few each, small enough that every total fits.
+    fn loads() -> impl Strategy<Value = Vec<Vec<u32>>> {
+        let elf = prop::collection::vec(0u32..1_000_000, 1..20);
+        prop::collection::vec(elf, 1..50)
+    }
+
+    fn format(loads: &[Vec<u32>]) -> String {
+        loads
+            .iter()
+            .map(|items| items.iter().map(u32::to_string).collect::<Vec<_>>().join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn elves() -> impl Strategy<Value = String> {
+        loads().prop_map(|loads| format(&loads))
+    }
+
+    /// Like [`elves`], but each elf ends with one to three blank lines.
+    fn spaced_elves() -> impl Strategy<Value = String> {
+        (loads(), prop::collection::vec(1usize..4, 50)).prop_map(|(loads, blanks)| {
+            loads
+                .iter()
+                .zip(blanks)
+                .map(|(items, blanks)| {
+                    let items: Vec<String> = items.iter().map(u32::to_string).collect();
+                    items.join("\n") + &"\n".repeat(blanks + 1)
+                })
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn top_three_carry_at_least_the_top_one(input in elves()) {
+            let part1: u64 = process_part1(&input).unwrap().parse().unwrap();
+            let part2: u64 = process_part2(&input).unwrap().parse().unwrap();
+            prop_assert!(part2 >= part1);
+        }
+
+        #[test]
+        fn top_k_matches_sorting_every_elf(loads in loads(), k in 0usize..60) {
+            let mut elves: Vec<Elf> = loads
+                .iter()
+                .enumerate()
+                .map(|(i, items)| Elf {
+                    index: i + 1,
+                    items: items.len(),
+                    total: items.iter().map(|&calories| u64::from(calories)).sum(),
+                })
+                .collect();
+            elves.sort_by_key(|elf| (Reverse(elf.total), elf.index));
+            elves.truncate(k);
+            prop_assert_eq!(top_k(&format(&loads), k).unwrap(), elves);
+        }
+
+        #[test]
+        fn streaming_gives_the_same_answers(input in spaced_elves()) {
+            prop_assert_eq!(stream_part1(input.as_bytes()), process_part1(&input));
+            prop_assert_eq!(stream_part2(input.as_bytes()), process_part2(&input));
+        }
+    }
 }
@@ -0,0 +1,162 @@
+use aoc_common::{normalize, Error};
+use rayon::prelude::*;
+
+use crate::{calories, loads, overflow, Elf, Heaviest};
+
+/// Roughly how many bytes of input each thread takes on at a time.
+const CHUNK: usize = 1 << 20;
+
+/// What one chunk of the input holds: its `k` heaviest elves, numbered
+/// from 1 within the chunk, and how many elves it has.
+struct Chunk {
+    heaviest: Vec<Elf>,
+    elves: usize,
+    /// The first elf in the chunk whose total overflows, if any.
+    overflow: Option<usize>,
+}
+
+/// Where the first run of blank lines at or after byte `from` starts, as
+/// the `"\n\n"` that does not follow another newline. Cutting there keeps
+/// the rest of the run at the start of the next chunk, where each of its
+/// blank lines still ends an elf.
+fn boundary(input: &str, mut from: usize) -> Option<usize> {
+    let bytes = input.as_bytes();
+    loop {
+        let at = from + bytes.get(from..)?.windows(2).position(|pair| pair == b"\n\n")?;
+        if at == 0 || bytes[at - 1] != b'\n' {
+            return Some(at);
+        }
+        from = at + 1;
+    }
+}
+
+/// Cuts `input` into chunks of about `size` bytes of whole elves, without
+/// the blank lines between chunks.
+fn chunks(input: &str, size: usize) -> Vec<&str> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    while let Some(end) = boundary(input, start + size.max(1)) {
+        chunks.push(&input[start..end]);
+        start = end + 2;
+    }
+    chunks.push(&input[start..]);
+    chunks
+}
+
+/// Sums the elves in `chunk`, a slice of `input`, locating bad items
+/// within the whole input.
+fn sum_chunk(input: &str, chunk: &str, k: usize) -> Result<Chunk, Error> {
+    let mut heaviest = Heaviest::new(k);
+    let mut overflow = None;
+    let mut elves = 0;
+    for (i, elf_load) in loads(chunk).into_iter().enumerate() {
+        let mut elf = Elf { index: i + 1, items: 0, total: 0 };
+        for item in elf_load {
+            let calories = calories(item).map_err(|err| err.at_fragment(input, item))?;
+            match elf.total.checked_add(calories) {
+                Some(total) => elf.total = total,
+                None => overflow = overflow.or(Some(elf.index)),
+            }
+            elf.items += 1;
+        }
+        heaviest.push(elf);
+        elves += 1;
+    }
+    Ok(Chunk { heaviest: heaviest.into_vec(), elves, overflow })
+}
+
+/// The `k` heaviest elves, summed a chunk of about `size` bytes at a time
+/// on the rayon thread pool. The answer and any error are the ones the
+/// sequential path gives: every item parses before totals can overflow.
+fn heaviest(input: &str, k: usize, size: usize) -> Result<Heaviest, Error> {
+    let input = normalize(input);
+    // Collected in order so the first bad item wins, as it would in turn.
+    let chunks: Vec<Result<Chunk, Error>> = chunks(&input, size)
+        .par_iter()
+        .map(|chunk| sum_chunk(&input, chunk, k))
+        .collect();
+    let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+    let mut heaviest = Heaviest::new(k);
+    let mut before = 0;
+    for chunk in chunks {
+        if let Some(index) = chunk.overflow {
+            return Err(overflow(before + index));
+        }
+        for elf in chunk.heaviest {
+            heaviest.push(Elf { index: before + elf.index, ..elf });
+        }
+        before += chunk.elves;
+    }
+    Ok(heaviest)
+}
+
+/// [`process_part1`](crate::process_part1) for huge inputs, summed in
+/// parallel.
+pub fn parallel_part1(input: &str) -> Result<String, Error> {
+    Ok(heaviest(input, 1, CHUNK)?.into_total().to_string())
+}
+
+/// [`process_part2`](crate::process_part2) for huge inputs, summed in
+/// parallel.
+pub fn parallel_part2(input: &str) -> Result<String, Error> {
+    Ok(heaviest(input, 3, CHUNK)?.into_total().to_string())
+}
+
+/// [`top_k`](crate::top_k) for huge inputs, summed in parallel.
+pub fn parallel_top_k(input: &str, k: usize) -> Result<Vec<Elf>, Error> {
+    Ok(heaviest(input, k, CHUNK)?.into_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::top_k;
+    use proptest::prelude::*;
+
+    #[test]
+    fn chunks_split_between_elves() {
+        let input = "1\n2\n\n3\n\n\n4\n\n\n\n5";
+        for size in 1..input.len() + 1 {
+            let joined = chunks(input, size).join("\n\n");
+            assert_eq!(joined, input);
+            let elves: Vec<Vec<&str>> = chunks(input, size)
+                .into_iter()
+                .flat_map(loads)
+                .collect();
+            assert_eq!(elves, loads(input), "size {size}");
+        }
+    }
+
+    #[test]
+    fn errors_match_the_sequential_path() {
+        let inputs = [
+            "1000\n\n20x0\n",
+            "18446744073709551615\n1\n\nx",
+            "1\n\n18446744073709551615\n1\n\n2",
+            "1\n\n\n2",
+        ];
+        for input in inputs {
+            for size in [1, 4, 100] {
+                let parallel = heaviest(input, 3, size).map(Heaviest::into_vec);
+                assert_eq!(parallel, top_k(input, 3), "{input:?}");
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn parallel_gives_the_same_answers(
+            elves in prop::collection::vec(prop::collection::vec(0u64..100_000, 0..6), 1..60),
+            k in 0usize..8,
+            size in 1usize..64,
+        ) {
+            let input = elves
+                .iter()
+                .map(|items| items.iter().map(u64::to_string).collect::<Vec<_>>().join("\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            prop_assert_eq!(heaviest(&input, k, size).map(Heaviest::into_vec), top_k(&input, k));
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Display,
+};
+
+use aoc_common::{normalize, Error, Solution};
+
+use crate::{Day1, Elf};
+
+/// Up to how many items [`rebalance`] searches for the best plan rather
+/// than settling for a greedy one.
+pub const EXACT_ITEMS: usize = 20;
+
+/// How to look for a plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    /// Branch and bound over every way to share the items out. The plan
+    /// is optimal, but the search can take exponential time.
+    Exact,
+    /// Longest processing time first: every item, heaviest first, goes to
+    /// the elf carrying the least so far. At most a third worse than the
+    /// best plan, in `O(n log n)`.
+    Greedy,
+}
+
+/// One item handed from an elf to another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    /// The elf giving the item away, 1-based.
+    pub from: usize,
+    /// Which of the giver's items it is, 1-based in input order.
+    pub item: usize,
+    pub calories: u64,
+    /// The elf receiving the item, 1-based.
+    pub to: usize,
+}
+
+/// The transfers that even out the loads, and the loads before and after.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub method: Method,
+    pub before: Vec<u64>,
+    pub after: Vec<u64>,
+    pub transfers: Vec<Transfer>,
+}
+
+impl Plan {
+    /// The heaviest load before the transfers.
+    pub fn max_before(&self) -> u64 {
+        self.before.iter().copied().max().unwrap_or(0)
+    }
+
+    /// The heaviest load after the transfers.
+    pub fn max_after(&self) -> u64 {
+        self.after.iter().copied().max().unwrap_or(0)
+    }
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let method = match self.method {
+            Method::Exact => "exact",
+            Method::Greedy => "greedy",
+        };
+        writeln!(
+            f,
+            "heaviest load {} -> {} ({method}), {} transfers",
+            self.max_before(),
+            self.max_after(),
+            self.transfers.len()
+        )?;
+        for Transfer { from, item, calories, to } in &self.transfers {
+            writeln!(f, "elf {from} gives item {item} ({calories} calories) to elf {to}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An item and who carries it now, both 0-based.
+struct Item {
+    elf: usize,
+    position: usize,
+    calories: u64,
+}
+
+/// The elves' loads when item `i` goes to elf `bins[i]`. Loads are wider
+/// than a `u64` because a greedy plan can end up heavier than the input.
+fn loads(items: &[Item], bins: &[usize], elves: usize) -> Vec<u128> {
+    let mut loads = vec![0u128; elves];
+    for (item, &bin) in items.iter().zip(bins) {
+        loads[bin] += u128::from(item.calories);
+    }
+    loads
+}
+
+fn heaviest(loads: &[u128]) -> u128 {
+    loads.iter().copied().max().unwrap_or(0)
+}
+
+/// Shares the items in `order` out greedily, heaviest first.
+fn greedy(items: &[Item], order: &[usize], elves: usize) -> Vec<usize> {
+    let mut bins = vec![0; items.len()];
+    let mut lightest: BinaryHeap<Reverse<(u128, usize)>> =
+        (0..elves).map(|bin| Reverse((0, bin))).collect();
+    for &i in order {
+        let Some(Reverse((load, bin))) = lightest.pop() else {
+            break;
+        };
+        bins[i] = bin;
+        lightest.push(Reverse((load + u128::from(items[i].calories), bin)));
+    }
+    bins
+}
+
+/// Moves items off the elves carrying more than `target`, heaviest first,
+/// to whoever carries the least, as long as that stays within `target`.
+/// Planners reach the same heaviest load this way with far fewer transfers
+/// than by sharing everything out anew, when it works at all.
+fn repair(items: &[Item], elves: usize, target: u128) -> Option<Vec<usize>> {
+    let mut bins: Vec<usize> = items.iter().map(|item| item.elf).collect();
+    let mut loads = loads(items, &bins, elves);
+    let mut lightest: BinaryHeap<Reverse<(u128, usize)>> =
+        loads.iter().enumerate().map(|(bin, &load)| Reverse((load, bin))).collect();
+
+    let mut carried: Vec<Vec<usize>> = vec![vec![]; elves];
+    for (i, item) in items.iter().enumerate() {
+        carried[item.elf].push(i);
+    }
+    for (elf, carried) in carried.iter_mut().enumerate() {
+        carried.sort_by_key(|&i| Reverse(items[i].calories));
+        for &i in carried.iter() {
+            if loads[elf] <= target {
+                break;
+            }
+            // Entries left behind by earlier moves no longer match the load.
+            while let Some(&Reverse((load, bin))) = lightest.peek() {
+                if load == loads[bin] {
+                    break;
+                }
+                lightest.pop();
+            }
+            let calories = u128::from(items[i].calories);
+            let &Reverse((load, bin)) = lightest.peek()?;
+            if bin == elf || load + calories > target {
+                continue;
+            }
+            lightest.pop();
+            bins[i] = bin;
+            loads[bin] += calories;
+            loads[elf] -= calories;
+            lightest.push(Reverse((loads[bin], bin)));
+            lightest.push(Reverse((loads[elf], elf)));
+        }
+        if loads[elf] > target {
+            return None;
+        }
+    }
+    Some(bins)
+}
+
+/// A depth-first search for the plan with the lightest heaviest load,
+/// pruning every branch that cannot beat the best plan found so far.
+struct Search<'a> {
+    items: &'a [Item],
+    order: &'a [usize],
+    loads: Vec<u128>,
+    bins: Vec<usize>,
+    best: u128,
+    best_bins: Vec<usize>,
+    /// No plan can be lighter than this, so finding one ends the search.
+    bound: u128,
+}
+
+impl Search<'_> {
+    fn run(&mut self, depth: usize, heaviest: u128) {
+        if self.best <= self.bound {
+            return;
+        }
+        let Some(&i) = self.order.get(depth) else {
+            self.best = heaviest;
+            self.best_bins.clone_from(&self.bins);
+            return;
+        };
+        let calories = u128::from(self.items[i].calories);
+        // Elves carrying as much are interchangeable, so try one of them.
+        let mut tried = vec![];
+        for bin in 0..self.loads.len() {
+            let load = self.loads[bin];
+            if tried.contains(&load) || load + calories >= self.best {
+                continue;
+            }
+            tried.push(load);
+            self.loads[bin] += calories;
+            self.bins[i] = bin;
+            self.run(depth + 1, heaviest.max(load + calories));
+            self.loads[bin] = load;
+        }
+    }
+}
+
+/// Matches each new load to the elf already holding most of its items, so
+/// few items change hands; greedily, so not always the fewest.
+fn owners(items: &[Item], bins: &[usize], elves: usize) -> Vec<usize> {
+    let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+    for (item, &bin) in items.iter().zip(bins) {
+        *shared.entry((bin, item.elf)).or_default() += 1;
+    }
+    let mut pairs: Vec<_> = shared.into_iter().collect();
+    pairs.sort_by_key(|&(pair, count)| (Reverse(count), pair));
+
+    let mut owner = vec![None; elves];
+    let mut taken = vec![false; elves];
+    for ((bin, elf), _) in pairs {
+        if owner[bin].is_none() && !taken[elf] {
+            owner[bin] = Some(elf);
+            taken[elf] = true;
+        }
+    }
+    let mut free = (0..elves).filter(|&elf| !taken[elf]);
+    owner
+        .into_iter()
+        .map(|elf| elf.or_else(|| free.next()).unwrap_or_default())
+        .collect()
+}
+
+/// Plans which items to move between the parsed elves so that the
+/// heaviest load is as light as `method` can make it. A plan is never
+/// worse than leaving every item where it is, and moves only items off the
+/// overloaded elves when that is enough.
+pub fn plan(parsed: &[Vec<u64>], method: Method) -> Result<Plan, Error> {
+    let before = parsed
+        .iter()
+        .enumerate()
+        .map(|(i, elf_load)| Ok(Elf::new(i + 1, elf_load)?.total))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let elves = parsed.len();
+    let items: Vec<Item> = parsed
+        .iter()
+        .enumerate()
+        .flat_map(|(elf, elf_load)| {
+            elf_load.iter().enumerate().map(move |(position, &calories)| Item { elf, position, calories })
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| Reverse(items[i].calories));
+
+    let unchanged: Vec<usize> = items.iter().map(|item| item.elf).collect();
+    let greedy = greedy(&items, &order, elves);
+    let mut bins = if heaviest(&loads(&items, &greedy, elves)) < heaviest(&loads(&items, &unchanged, elves)) {
+        greedy
+    } else {
+        unchanged
+    };
+
+    if method == Method::Exact {
+        let total: u128 = items.iter().map(|item| u128::from(item.calories)).sum();
+        let largest = order.first().map_or(0, |&i| u128::from(items[i].calories));
+        let mut search = Search {
+            items: &items,
+            order: &order,
+            loads: vec![0; elves],
+            bins: vec![0; items.len()],
+            best: heaviest(&loads(&items, &bins, elves)),
+            best_bins: bins,
+            bound: largest.max(total.div_ceil(elves.max(1) as u128)),
+        };
+        search.run(0, 0);
+        bins = search.best_bins;
+    }
+    if let Some(repaired) = repair(&items, elves, heaviest(&loads(&items, &bins, elves))) {
+        bins = repaired;
+    }
+
+    let owner = owners(&items, &bins, elves);
+    let after = loads(&items, &bins, elves);
+    let mut transfers: Vec<Transfer> = items
+        .iter()
+        .zip(&bins)
+        .filter(|(item, &bin)| owner[bin] != item.elf)
+        .map(|(item, &bin)| Transfer {
+            from: item.elf + 1,
+            item: item.position + 1,
+            calories: item.calories,
+            to: owner[bin] + 1,
+        })
+        .collect();
+    transfers.sort_by_key(|transfer| (transfer.from, transfer.item));
+
+    let mut loads_after = vec![0; elves];
+    for (bin, load) in after.into_iter().enumerate() {
+        // No load is heavier than the heaviest one before, which fitted.
+        loads_after[owner[bin]] = load as u64;
+    }
+    Ok(Plan { method, before, after: loads_after, transfers })
+}
+
+/// Plans transfers for the elves in the puzzle input, exactly for up to
+/// [`EXACT_ITEMS`] items and greedily beyond.
+pub fn rebalance(input: &str) -> Result<Plan, Error> {
+    let parsed = Day1::parse(&normalize(input))?;
+    let items: usize = parsed.iter().map(Vec::len).sum();
+    let method = if items <= EXACT_ITEMS { Method::Exact } else { Method::Greedy };
+    plan(&parsed, method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    /// The loads after carrying out every transfer in `plan`.
+    fn carry_out(parsed: &[Vec<u64>], plan: &Plan) -> Vec<u64> {
+        let mut loads: Vec<u64> = parsed.iter().map(|elf_load| elf_load.iter().sum()).collect();
+        for &Transfer { from, item, calories, to } in &plan.transfers {
+            assert_eq!(parsed[from - 1][item - 1], calories);
+            loads[from - 1] -= calories;
+            loads[to - 1] += calories;
+        }
+        loads
+    }
+
+    #[test]
+    fn exact_plans_are_optimal() {
+        let plan = rebalance(INPUT).unwrap();
+        assert_eq!(plan.method, Method::Exact);
+        assert_eq!((plan.max_before(), plan.max_after()), (24000, 11000));
+        assert_eq!(plan.after, [11000; 5]);
+        assert_eq!(carry_out(&Day1::parse(INPUT).unwrap(), &plan), plan.after);
+        assert!(plan.to_string().starts_with("heaviest load 24000 -> 11000 (exact), "));
+    }
+
+    #[test]
+    fn greedy_plans_never_make_things_worse() {
+        let parsed: Vec<Vec<u64>> = (1..=60).map(|i| vec![i * 7 % 50 + 1; (i % 4 + 1) as usize]).collect();
+        let plan = plan(&parsed, Method::Greedy).unwrap();
+        assert_eq!(carry_out(&parsed, &plan), plan.after);
+        assert!(plan.max_after() < plan.max_before());
+
+        // Only the overloaded elf gives anything away when that is enough.
+        let repaired = super::plan(&[vec![9, 1], vec![2], vec![3]], Method::Greedy).unwrap();
+        assert_eq!(repaired.transfers, [Transfer { from: 1, item: 2, calories: 1, to: 2 }]);
+        assert_eq!(repaired.after, [9, 3, 3]);
+
+        let balanced = super::plan(&[vec![5], vec![3, 2]], Method::Greedy).unwrap();
+        assert_eq!((balanced.transfers, balanced.after), (vec![], vec![5, 5]));
+    }
+}
@@ -0,0 +1,202 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use aoc_common::{normalize, Error, Solution};
+
+use crate::{Day1, Elf};
+
+/// The percentiles a [`Report`] lists.
+pub const PERCENTILES: [u8; 6] = [10, 25, 50, 75, 90, 99];
+
+/// How many equally wide buckets the histogram has at most.
+const BUCKETS: u64 = 10;
+
+/// The width of the longest histogram bar.
+const BAR: usize = 40;
+
+/// A range of loads in the histogram and how many elves carry one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: u64,
+    /// Inclusive.
+    pub end: u64,
+    pub elves: usize,
+}
+
+/// Statistics over every elf's load.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub elves: usize,
+    pub total: u128,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    /// The mean of the two middle loads when there is an even number.
+    pub median: f64,
+    /// Nearest-rank percentiles of the loads, one per [`PERCENTILES`].
+    pub percentiles: Vec<(u8, u64)>,
+    /// How many elves carry each number of items.
+    pub items_per_elf: BTreeMap<usize, usize>,
+    /// Elves carrying no calories at all.
+    pub empty: usize,
+    pub histogram: Vec<Bucket>,
+}
+
+impl Report {
+    pub fn new(parsed: &[Vec<u64>]) -> Result<Self, Error> {
+        let elves = parsed
+            .iter()
+            .enumerate()
+            .map(|(i, elf_load)| Elf::new(i + 1, elf_load))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut loads: Vec<u64> = elves.iter().map(|elf| elf.total).collect();
+        loads.sort_unstable();
+        let total: u128 = loads.iter().map(|&load| u128::from(load)).sum();
+        let (min, max) = (loads.first().copied(), loads.last().copied());
+
+        let mut items_per_elf = BTreeMap::new();
+        for elf in &elves {
+            *items_per_elf.entry(elf.items).or_insert(0) += 1;
+        }
+
+        Ok(Report {
+            elves: loads.len(),
+            total,
+            min: min.unwrap_or(0),
+            max: max.unwrap_or(0),
+            mean: if loads.is_empty() { 0.0 } else { total as f64 / loads.len() as f64 },
+            median: median(&loads),
+            percentiles: PERCENTILES
+                .iter()
+                .filter_map(|&p| Some((p, percentile(&loads, p)?)))
+                .collect(),
+            items_per_elf,
+            empty: loads.iter().take_while(|&&load| load == 0).count(),
+            histogram: histogram(&loads),
+        })
+    }
+
+    /// The report as `section,label,value` rows under a header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("section,label,value\n");
+        let mut row = |section: &str, label: &str, value: &dyn Display| {
+            csv += &format!("{section},{label},{value}\n");
+        };
+        row("summary", "elves", &self.elves);
+        row("summary", "total", &self.total);
+        row("summary", "min", &self.min);
+        row("summary", "max", &self.max);
+        row("summary", "mean", &self.mean);
+        row("summary", "median", &self.median);
+        row("summary", "empty", &self.empty);
+        for (p, load) in &self.percentiles {
+            row("percentile", &format!("p{p}"), load);
+        }
+        for (items, elves) in &self.items_per_elf {
+            row("items_per_elf", &items.to_string(), elves);
+        }
+        for Bucket { start, end, elves } in &self.histogram {
+            row("histogram", &format!("{start}-{end}"), elves);
+        }
+        csv
+    }
+}
+
+/// The middle of the sorted `loads`.
+fn median(loads: &[u64]) -> f64 {
+    match loads.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => loads[n / 2] as f64,
+        n => (loads[n / 2 - 1] as f64 + loads[n / 2] as f64) / 2.0,
+    }
+}
+
+/// The smallest of the sorted `loads` that at least `p` percent of them
+/// do not exceed.
+fn percentile(loads: &[u64], p: u8) -> Option<u64> {
+    let rank = (usize::from(p) * loads.len()).div_ceil(100).max(1);
+    loads.get(rank - 1).copied()
+}
+
+/// Splits the range of the sorted `loads` into equally wide buckets.
+fn histogram(loads: &[u64]) -> Vec<Bucket> {
+    let (Some(&min), Some(&max)) = (loads.first(), loads.last()) else {
+        return vec![];
+    };
+    let width = (max - min) / BUCKETS + 1;
+    let mut buckets: Vec<Bucket> = (0..=(max - min) / width)
+        .map(|i| {
+            let start = min + i * width;
+            Bucket { start, end: start.saturating_add(width - 1), elves: 0 }
+        })
+        .collect();
+    for &load in loads {
+        buckets[((load - min) / width) as usize].elves += 1;
+    }
+    buckets
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "elves          {}", self.elves)?;
+        writeln!(f, "total          {}", self.total)?;
+        writeln!(f, "min            {}", self.min)?;
+        writeln!(f, "max            {}", self.max)?;
+        writeln!(f, "mean           {:.1}", self.mean)?;
+        writeln!(f, "median         {:.1}", self.median)?;
+        for (p, load) in &self.percentiles {
+            writeln!(f, "p{p:<13} {load}")?;
+        }
+        writeln!(f, "empty elves    {}", self.empty)?;
+
+        writeln!(f, "\nitems per elf")?;
+        for (items, elves) in &self.items_per_elf {
+            writeln!(f, "{items:>5}  {elves} elves")?;
+        }
+
+        writeln!(f, "\nhistogram")?;
+        let most = self.histogram.iter().map(|bucket| bucket.elves).max().unwrap_or(0);
+        let width = self.max.to_string().len();
+        for Bucket { start, end, elves } in &self.histogram {
+            let bar = "#".repeat((elves * BAR).div_ceil(most.max(1)));
+            writeln!(f, "{start:>width$}-{end:<width$} |{bar} {elves}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Statistics over the loads in the puzzle input.
+pub fn report(input: &str) -> Result<Report, Error> {
+    Report::new(&Day1::parse(&normalize(input))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    #[test]
+    fn summarises_the_loads() {
+        let report = report(INPUT).unwrap();
+        assert_eq!((report.elves, report.total, report.min, report.max), (5, 55000, 4000, 24000));
+        assert_eq!((report.mean, report.median), (11000.0, 10000.0));
+        assert_eq!(
+            report.percentiles,
+            [(10, 4000), (25, 6000), (50, 10000), (75, 11000), (90, 24000), (99, 24000)]
+        );
+        assert_eq!(report.items_per_elf, BTreeMap::from([(1, 2), (2, 1), (3, 2)]));
+        assert_eq!(report.empty, 0);
+        assert_eq!(report.histogram.iter().map(|b| b.elves).sum::<usize>(), 5);
+        assert_eq!(report.histogram[0], Bucket { start: 4000, end: 6000, elves: 2 });
+    }
+
+    #[test]
+    fn exports_csv() {
+        let csv = report("0\n\n3\n4").unwrap().to_csv();
+        assert!(csv.starts_with("section,label,value\nsummary,elves,2\nsummary,total,7\n"));
+        assert!(csv.contains("summary,median,3.5\nsummary,empty,1\n"));
+        assert!(csv.contains("items_per_elf,1,1\nitems_per_elf,2,1\n"));
+        assert!(csv.ends_with("histogram,0-0,1\nhistogram,1-1,0\nhistogram,2-2,0\nhistogram,3-3,0\nhistogram,4-4,0\nhistogram,5-5,0\nhistogram,6-6,0\nhistogram,7-7,1\n"));
+    }
+}
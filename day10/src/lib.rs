@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{io::BufRead, str::FromStr};
+
+use aoc_common::{for_each_line, normalize, Error, Frame, Grid, Point, Solution, Visualize};
+use tracing::{debug, instrument};
 
 #[derive(Debug, Copy, Clone)]
 enum Op {
@@ -7,88 +10,126 @@ enum Op {
 }
 
 #[derive(Clone, Copy, Debug)]
-struct Command {
+pub struct Command {
     operation: Op,
     arg: i32
 }
 
 impl FromStr for Command {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split_whitespace().collect();
+        let (operation, arg) = s.split_once(' ').unwrap_or((s, ""));
 
-        match parts[0] {
+        match operation {
             "noop" => {
                 Ok(Command { operation: Op::Noop, arg: 0})
             },
             "addx" => {
-                let arg = parts[1].parse::<i32>().expect(&"Invalid arg".to_string());
+                let arg = arg.parse::<i32>().map_err(|err| {
+                    Error::new(Day10::DAY, format!("invalid argument {arg:?}: {err}"))
+                        .at_fragment(s, arg)
+                })?;
                 Ok(Command { operation: Op::Addx, arg})
             },
-            _ => return Err("Unknown direction".to_string()),
+            _ => Err(Error::new(Day10::DAY, format!("unknown instruction {operation:?}"))
+                .at(1, 1)),
         }
     }
 }
 
-pub fn part1(input: &str) -> String {
-    let commands: Vec<Command> = input
-        .lines()
-        .map(|s| s.parse::<Command>().unwrap())
-        .collect();
-    let mut signal_strengths: Vec<i32> = vec![];
-    let mut x_register = 1;
+impl Command {
+    /// The instruction, `noop` or `addx`.
+    pub fn instruction(&self) -> &'static str {
+        match self.operation {
+            Op::Noop => "noop",
+            Op::Addx => "addx",
+        }
+    }
 
-    for command in commands {
-        match command.operation {
+    /// What `addx` adds to the X register; `noop` takes no argument.
+    pub fn argument(&self) -> Option<i32> {
+        match self.operation {
+            Op::Noop => None,
+            Op::Addx => Some(self.arg),
+        }
+    }
+
+    /// Runs the command against the X register, calling `cycle` with the
+    /// value of the register during each cycle the command takes.
+    ///
+    /// The register is wider than the `addx` arguments, so it cannot
+    /// overflow within any input that fits on a disk.
+    fn run(self, x_register: &mut i64, mut cycle: impl FnMut(i64)) {
+        match self.operation {
             Op::Addx => {
-                signal_strengths.push(x_register);
-                signal_strengths.push(x_register);
-                x_register += command.arg;
+                cycle(*x_register);
+                cycle(*x_register);
+                *x_register += i64::from(self.arg);
             },
             Op::Noop => {
-                signal_strengths.push(x_register);
+                cycle(*x_register);
             }
         }
     }
+}
 
-    let mut i = 20;
-    let mut sum = 0;
-    while i < signal_strengths.len() {
-        dbg!(signal_strengths[i]);
-        sum += i as i32 * signal_strengths[i - 1];
-        i += 40;
+/// The value of the X register during each cycle, starting with cycle 1.
+#[instrument(level = "debug", skip_all, fields(commands = commands.len()))]
+fn register_values(commands: &[Command]) -> Vec<i64> {
+    let mut signal_strengths: Vec<i64> = vec![];
+    let mut x_register: i64 = 1;
+
+    for command in commands {
+        command.run(&mut x_register, |x| signal_strengths.push(x));
     }
-    sum.to_string()
+    signal_strengths
 }
 
-pub fn part2(input: &str) -> String {
-    let commands: Vec<Command> = input
-        .lines()
-        .map(|s| s.parse::<Command>().unwrap())
-        .collect();
-    let mut signal_strengths: Vec<i32> = vec![];
-    let mut x_register = 1;
+/// Adds up the signal strength during the 20th cycle and every 40th one
+/// after it, fed the X register one cycle at a time. A cycle only counts
+/// once the program runs on past it.
+#[derive(Default)]
+struct SignalStrengths {
+    cycles: usize,
+    previous: i64,
+    sum: i64,
+}
 
-    for command in commands {
-        match command.operation {
-            Op::Addx => {
-                signal_strengths.push(x_register);
-                signal_strengths.push(x_register);
-                x_register += command.arg;
-            },
-            Op::Noop => {
-                signal_strengths.push(x_register);
-            }
+impl SignalStrengths {
+    fn push(&mut self, x: i64) -> Result<(), Error> {
+        let i = self.cycles;
+        if i >= 20 && (i - 20).is_multiple_of(40) {
+            debug!(cycle = i, x = self.previous, "sampled the X register");
+            self.sum = (i as i64)
+                .checked_mul(self.previous)
+                .and_then(|strength| self.sum.checked_add(strength))
+                .ok_or_else(|| {
+                    Error::new(Day10::DAY, format!("signal strength overflows at cycle {i}"))
+                })?;
         }
+        self.cycles += 1;
+        self.previous = x;
+        Ok(())
+    }
+}
+
+/// Draws the six rows of the screen from the first 240 register values.
+fn draw(signal_strengths: &[i64]) -> Result<String, Error> {
+    if signal_strengths.len() < 240 {
+        return Err(Error::new(
+            Day10::DAY,
+            format!(
+                "the program runs for {} cycles, the screen needs 240",
+                signal_strengths.len()
+            ),
+        ));
     }
 
     let mut screen: String = String::new();
     for c in 0..6 {
         for i in 0..40 {
-            let sprite_position = signal_strengths[40 * c + i];
-
-            if ((sprite_position-1)..=(sprite_position+1)).contains(&(i as i32)) {
+            if lit(signal_strengths[40 * c + i], i) {
                 screen.push('#');
             } else {
                 screen.push('.');
@@ -96,7 +137,113 @@ pub fn part2(input: &str) -> String {
         }
         screen.push('\n');
     }
-    screen
+    Ok(screen)
+}
+
+/// Runs the program in `input` one line at a time, calling `cycle` with
+/// the X register during each cycle.
+fn stream_register_values(input: impl BufRead, mut cycle: impl FnMut(i64) -> Result<(), Error>) -> Result<(), Error> {
+    let mut x_register: i64 = 1;
+    for_each_line(Day10::DAY, input, |number, line| {
+        let command: Command = line.parse().map_err(|err: Error| err.on_line(number))?;
+        let mut result = Ok(());
+        command.run(&mut x_register, |x| {
+            if result.is_ok() {
+                result = cycle(x);
+            }
+        });
+        result
+    })
+}
+
+/// Whether the sprite centred on `x` covers the pixel in `column`.
+fn lit(x: i64, column: usize) -> bool {
+    x.abs_diff(column as i64) <= 1
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Parsed<'a> = Vec<Command>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, s)| s.parse::<Command>().map_err(|err| err.on_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let mut signal_strengths = SignalStrengths::default();
+        for x in register_values(parsed) {
+            signal_strengths.push(x)?;
+        }
+        Ok(signal_strengths.sum.to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        draw(&register_values(parsed))
+    }
+}
+
+impl Visualize for Day10 {
+    /// One frame per cycle: the screen drawn so far above the sprite's row.
+    /// Part 1 also keeps a running total of the sampled signal strengths,
+    /// counting a cycle once the program runs past it as the answer does.
+    fn frames(
+        parsed: &Self::Parsed<'_>,
+        part: u8,
+        frame: &mut dyn FnMut(Frame),
+    ) -> Result<(), Error> {
+        let mut screen = Grid::filled(40, 7, ' ');
+        let mut signal_strengths = SignalStrengths::default();
+        for (i, &x) in register_values(parsed).iter().enumerate().take(240) {
+            let (cycle, column) = (i + 1, i % 40);
+            screen[Point::new(column as i64, (i / 40) as i64)] = if lit(x, column) { '#' } else { '.' };
+            for column in 0..40 {
+                screen[Point::new(column, 6)] = if lit(x, column as usize) { '=' } else { ' ' };
+            }
+
+            let mut caption = format!("cycle {cycle}, X = {x}");
+            if part == 1 {
+                signal_strengths.push(x)?;
+                caption += &format!(", signal strength sum {}", signal_strengths.sum);
+            }
+            frame(Frame { caption, cells: screen.clone() });
+        }
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<String, Error> {
+    Day10::part1(&Day10::parse(&normalize(input))?)
+}
+
+pub fn part2(input: &str) -> Result<String, Error> {
+    Day10::part2(&Day10::parse(&normalize(input))?)
+}
+
+/// [`part1`] for an input read one line at a time.
+pub fn stream_part1(input: impl BufRead) -> Result<String, Error> {
+    let mut signal_strengths = SignalStrengths::default();
+    stream_register_values(input, |x| signal_strengths.push(x))?;
+    Ok(signal_strengths.sum.to_string())
+}
+
+/// [`part2`] for an input read one line at a time, keeping only the
+/// cycles the screen shows.
+pub fn stream_part2(input: impl BufRead) -> Result<String, Error> {
+    let mut signal_strengths = Vec::with_capacity(240);
+    stream_register_values(input, |x| {
+        if signal_strengths.len() < 240 {
+            signal_strengths.push(x);
+        }
+        Ok(())
+    })?;
+    draw(&signal_strengths)
 }
 
 #[cfg(test)]
@@ -259,13 +406,43 @@ noop";
 ";
     #[test]
     fn part1_works() {
-        let result = part1(INPUT);
+        let result = part1(INPUT).unwrap();
         assert_eq!(result, "13140");
     }
 
     #[test]
     fn part2_works() {
-        let result = part2(INPUT);
+        let result = part2(INPUT).unwrap();
         assert_eq!(result, PART2_OUTPUT);
     }
+
+    #[test]
+    fn streaming_matches_the_whole_input() {
+        let windows = INPUT.replace('\n', "\r\n");
+        for input in [INPUT, &windows, "", "noop\naddx 1\naddx x"] {
+            assert_eq!(stream_part1(input.as_bytes()), part1(input), "{input:?}");
+            assert_eq!(stream_part2(input.as_bytes()), part2(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn frames_draw_the_screen() {
+        let commands = Day10::parse(INPUT).unwrap();
+        let mut frames = vec![];
+        Day10::frames(&commands, 1, &mut |frame| frames.push(frame)).unwrap();
+        assert_eq!(frames.len(), 240);
+        assert_eq!(frames[220].caption, "cycle 221, X = 19, signal strength sum 13140");
+        let last = frames[239].cells.to_string();
+        assert!(last.starts_with(PART2_OUTPUT));
+    }
+
+    #[test]
+    fn the_last_caption_matches_the_answer() {
+        let input = "noop\n".repeat(20);
+        let commands = Day10::parse(&input).unwrap();
+        let mut frames = vec![];
+        Day10::frames(&commands, 1, &mut |frame| frames.push(frame)).unwrap();
+        let caption = &frames.last().unwrap().caption;
+        assert!(caption.ends_with(&format!("sum {}", part1(&input).unwrap())), "{caption}");
+    }
 }
@@ -1,74 +1,101 @@
-use std::{collections::VecDeque, fmt::Display};
+use std::collections::VecDeque;
+
+use aoc_common::{entry, finish, normalize, Error, Frame, ParseResult, Solution, Visualize};
+use tracing::{debug, instrument, trace_span};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{multispace1, newline},
+    character::complete::{multispace1, one_of},
+    error::context,
     multi::separated_list1,
     sequence::{delimited, preceded},
     *
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Value {
     Old,
     Num(u64),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Operation {
     Mul((Value, Value)),
     Add((Value, Value)),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct Test {
     divisible: u64,
     true_recipient: u64,
     false_recipient: u64,
 }
 
-#[derive(Debug)]
-struct Monkey {
+#[derive(Debug, Clone)]
+pub struct Monkey {
     items: VecDeque<u64>,
     operation: Operation,
     test: Test,
     touch_count: u64,
+    /// Where the monkey's block starts in the input.
+    line: usize,
 }
 
 impl Monkey {
-    fn inspect(
-        &mut self,
-        lower_worry_level: bool,
-        magic_trick: u64
-    ) -> u64{
+    /// The worry levels of the items the monkey holds, next first.
+    pub fn items(&self) -> impl Iterator<Item = u64> + '_ {
+        self.items.iter().copied()
+    }
+
+    /// How the worry level changes, such as `old * 19`.
+    pub fn operation(&self) -> String {
+        let value = |value: &Value| match value {
+            Value::Old => "old".to_string(),
+            Value::Num(num) => num.to_string(),
+        };
+        match &self.operation {
+            Operation::Mul((a, b)) => format!("{} * {}", value(a), value(b)),
+            Operation::Add((a, b)) => format!("{} + {}", value(a), value(b)),
+        }
+    }
+
+    /// What the worry level is tested for being divisible by.
+    pub fn divisor(&self) -> u64 {
+        self.test.divisible
+    }
+
+    /// Which monkeys the item goes to when the test passes and when it
+    /// fails.
+    pub fn recipients(&self) -> (u64, u64) {
+        (self.test.true_recipient, self.test.false_recipient)
+    }
+
+    /// The worry level of the next item once inspected: divided by three
+    /// when `lower_worry_level`, kept below `modulus` otherwise. `None`
+    /// when the exact level no longer fits in 64 bits.
+    fn inspect(&mut self, lower_worry_level: bool, modulus: u64) -> Option<u64> {
         self.touch_count += 1;
-        let item = self.items.pop_front().unwrap();
+        let item = u128::from(self.items.pop_front().unwrap());
+        let operand = |value: &Value| match value {
+            Value::Old => item,
+            Value::Num(num) => u128::from(*num),
+        };
+        // Two 64-bit operands cannot overflow 128 bits.
         let worry_level = match &self.operation {
-            Operation::Mul((_, b)) => {
-                let num2 = match b {
-                    Value::Old => item,
-                    Value::Num(num) => *num,
-                };
-                (item * num2) % magic_trick
-            },
-            Operation::Add((_, b)) => {
-                let num2 = match b {
-                    Value::Old => item,
-                    Value::Num(num) => *num,
-                };
-                (item + num2) % magic_trick
-            }
+            Operation::Mul((_, b)) => item * operand(b),
+            Operation::Add((_, b)) => item + operand(b),
         };
         if lower_worry_level {
-            worry_level / 3
+            // Reducing first would change the quotient, so part 1 stays exact.
+            u64::try_from(worry_level / 3).ok()
         } else {
-            worry_level
+            Some((worry_level % u128::from(modulus)) as u64)
         }
     }
 
     fn test(&self, new_item: u64) -> u64 {
-        if new_item % self.test.divisible == 0 {
+        if new_item.is_multiple_of(self.test.divisible) {
             self.test.true_recipient
         } else {
             self.test.false_recipient
@@ -76,47 +103,47 @@ impl Monkey {
     }
 }
 
-fn value(input: &str) -> IResult<&str, Value> {
+fn value(input: &str) -> ParseResult<'_, Value> {
     alt((
         tag("old").map(|_| Value::Old),
         nom::character::complete::u64
-            .map(|num| Value::Num(num))
+            .map(Value::Num)
     ))(input)
 }
 
-fn operation(input: &str) -> IResult<&str, Operation> {
+fn operation(input: &str) -> ParseResult<'_, Operation> {
     let (input, _) = tag("Operation: new = ")(input)?;
     let (input, value_1) = value(input)?;
     let (input, operator) = delimited(
         multispace1,
-        alt((tag("*"), tag("+"))),
+        one_of("*+"),
         multispace1
     )(input)?;
     let (input, value_2) = value(input)?;
 
-    let result = match operator {
-        "*" => Operation::Mul((value_1, value_2)),
-        "+" => Operation::Add((value_1, value_2)),
-        _ => panic!("unknown operator"),
+    let result = if operator == '*' {
+        Operation::Mul((value_1, value_2))
+    } else {
+        Operation::Add((value_1, value_2))
     };
     Ok((input, result))
 }
 
-fn test(input: &str) -> IResult<&str, Test> {
-    let (input, divisible) = preceded(
+fn test(input: &str) -> ParseResult<'_, Test> {
+    let (input, divisible) = context("expected `Test: divisible by N`", preceded(
         tag("Test: divisible by "),
         nom::character::complete::u64,
-    )(input)?;
+    ))(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, true_recipient) = preceded(
+    let (input, true_recipient) = context("expected `If true: throw to monkey N`", preceded(
         tag("If true: throw to monkey "),
         nom::character::complete::u64,
-    )(input)?;
+    ))(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, false_recipient) = preceded(
+    let (input, false_recipient) = context("expected `If false: throw to monkey N`", preceded(
         tag("If false: throw to monkey "),
         nom::character::complete::u64,
-    )(input)?;
+    ))(input)?;
 
     Ok((
         input,
@@ -128,22 +155,22 @@ fn test(input: &str) -> IResult<&str, Test> {
     ))
 }
 
-fn monkey(input: &str) -> IResult<&str, Monkey> {
-    let (input, _id) = delimited(
+fn monkey(input: &str) -> ParseResult<'_, Monkey> {
+    let (input, _id) = context("expected `Monkey N:`", delimited(
         tag("Monkey "),
         nom::character::complete::u64,
         tag(":"),
-    )(input)?;
+    ))(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, items) = preceded(
+    let (input, items) = context("expected `Starting items: A, B, ...`", preceded(
         tag("Starting items: "),
         separated_list1(
             tag(", "),
             nom::character::complete::u64,
         )
-    )(input)?;
+    ))(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, op) = operation(input)?;
+    let (input, op) = context("expected `Operation: new = ...`", operation)(input)?;
     let (input, _) = multispace1(input)?;
     let (input, test) = test(input)?;
 
@@ -154,70 +181,191 @@ fn monkey(input: &str) -> IResult<&str, Monkey> {
             operation: op,
             test,
             touch_count: 0,
+            line: 1,
         }
     ))
 }
 
-pub fn part1(input: &str) -> String {
-    let (_, mut monkeys) = separated_list1(tag("\n\n"), monkey)(input).unwrap();
-    let magic_trick = monkeys
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Without the relief of part 1, worry levels only matter modulo each
+/// divisor, so they can be kept below the least common multiple of all of
+/// them.
+fn magic_trick(monkeys: &[Monkey]) -> Result<u64, Error> {
+    monkeys
         .iter()
-        .map(|monkey| monkey.test.divisible)
-        .product::<u64>();
+        .try_fold(1u64, |acc, monkey| {
+            let divisible = monkey.test.divisible;
+            (acc / gcd(acc, divisible)).checked_mul(divisible)
+        })
+        .ok_or_else(|| Error::new(Day11::DAY, "the divisors' least common multiple overflows 64 bits"))
+}
 
-    for _round in 0..20 {
+/// Plays `rounds` rounds of keep away; `observe` sees the monkeys after
+/// each of them.
+#[instrument(level = "debug", skip(monkeys, observe), fields(monkeys = monkeys.len()))]
+fn play(
+    monkeys: &[Monkey],
+    rounds: usize,
+    lower_worry_level: bool,
+    mut observe: impl FnMut(usize, &[Monkey]),
+) -> Result<Vec<Monkey>, Error> {
+    let mut monkeys = monkeys.to_vec();
+    let magic_trick = if lower_worry_level {
+        u64::MAX
+    } else {
+        let magic_trick = magic_trick(&monkeys)?;
+        debug!(magic_trick, "worry levels are kept modulo the divisors' LCM");
+        magic_trick
+    };
+
+    for round in 0..rounds {
+        let _round = trace_span!("round", round).entered();
         for monkey_index in 0..monkeys.len() {
             for _i in 0..monkeys[monkey_index].items.len() {
-                let monkey = monkeys.get_mut(monkey_index).unwrap();
-                let new_item = monkey.inspect(true, magic_trick);
+                let monkey = &mut monkeys[monkey_index];
+                let new_item = monkey.inspect(lower_worry_level, magic_trick).ok_or_else(|| {
+                    Error::new(
+                        Day11::DAY,
+                        format!("monkey {monkey_index}: a worry level overflows 64 bits in round {}", round + 1),
+                    )
+                    .at(monkey.line, 1)
+                })?;
                 let recepient = monkey.test(new_item);
-                monkeys
-                    .get_mut(recepient as usize)
-                    .unwrap()
+                monkeys[recepient as usize]
                     .items
                     .push_back(new_item);
             }
         }
+        observe(round, &monkeys);
     }
-    monkeys.sort_by_key(|monkey| monkey.touch_count);
-    monkeys
+    Ok(monkeys)
+}
+
+/// How many items each monkey inspected over `rounds` rounds, dividing
+/// worry levels by three after every inspection when `lower_worry_level`.
+pub fn inspection_counts(
+    monkeys: &[Monkey],
+    rounds: usize,
+    lower_worry_level: bool,
+) -> Result<Vec<u64>, Error> {
+    let monkeys = play(monkeys, rounds, lower_worry_level, |_, _| {})?;
+    Ok(monkeys.iter().map(|monkey| monkey.touch_count).collect())
+}
+
+fn monkey_business(
+    monkeys: &[Monkey],
+    rounds: usize,
+    lower_worry_level: bool,
+) -> Result<u64, Error> {
+    let mut counts = inspection_counts(monkeys, rounds, lower_worry_level)?;
+    counts.sort();
+    counts
         .iter()
         .rev()
         .take(2)
-        .map(|monkey| monkey.touch_count)
-        .product::<u64>()
-        .to_string()
+        .try_fold(1u64, |acc, &count| acc.checked_mul(count))
+        .ok_or_else(|| Error::new(Day11::DAY, "monkey business overflows 64 bits"))
 }
 
-pub fn part2(input: &str) -> String {
-    let (_, mut monkeys) = separated_list1(tag("\n\n"), monkey)(input).unwrap();
-    let magic_trick = monkeys
-        .iter()
-        .map(|monkey| monkey.test.divisible)
-        .product::<u64>();
+pub struct Day11;
 
-    for _round in 0..10_000 {
-        for monkey_index in 0..monkeys.len() {
-            for _i in 0..monkeys[monkey_index].items.len() {
-                let monkey = monkeys.get_mut(monkey_index).unwrap();
-                let new_item = monkey.inspect(false, magic_trick);
-                let recepient = monkey.test(new_item);
-                monkeys
-                    .get_mut(recepient as usize)
-                    .unwrap()
-                    .items
-                    .push_back(new_item);
-            }
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Parsed<'a> = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        let mut monkeys = finish(
+            Self::DAY,
+            input,
+            separated_list1(tag("\n\n"), entry("expected a `Monkey N:` block", monkey))(input),
+        )?;
+
+        for (monkey, (offset, _)) in monkeys.iter_mut().zip(input.match_indices("Monkey ")) {
+            monkey.line = input[..offset].matches('\n').count() + 1;
+        }
+        for (i, monkey) in monkeys.iter().enumerate() {
+            let Test { divisible, true_recipient, false_recipient } = monkey.test;
+            let message = if divisible == 0 {
+                "cannot test divisibility by 0".to_string()
+            } else if let Some(recipient) = [true_recipient, false_recipient]
+                .into_iter()
+                .find(|&recipient| recipient >= monkeys.len() as u64)
+            {
+                format!("there is no monkey {recipient} to throw to")
+            } else {
+                continue;
+            };
+            return Err(Error::new(Self::DAY, format!("monkey {i}: {message}")).at(monkey.line, 1));
         }
+        Ok(monkeys)
     }
-    monkeys.sort_by_key(|monkey| monkey.touch_count);
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(monkey_business(parsed, 20, true)?.to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(monkey_business(parsed, 10_000, false)?.to_string())
+    }
+}
+
+/// One line per monkey, listing the items it holds.
+fn draw(monkeys: &[Monkey]) -> Vec<String> {
     monkeys
         .iter()
-        .rev()
-        .take(2)
-        .map(|monkey| monkey.touch_count)
-        .product::<u64>()
-        .to_string()
+        .enumerate()
+        .map(|(i, monkey)| {
+            let items: Vec<String> = monkey.items.iter().map(u64::to_string).collect();
+            format!("Monkey {i} (inspected {}): {}", monkey.touch_count, items.join(", "))
+        })
+        .collect()
+}
+
+impl Visualize for Day11 {
+    fn frames(
+        parsed: &Self::Parsed<'_>,
+        part: u8,
+        frame: &mut dyn FnMut(Frame),
+    ) -> Result<(), Error> {
+        let (rounds, lower_worry_level) = if part == 1 { (20, true) } else { (10_000, false) };
+
+        // Wide enough for every item in one monkey's hands, each with as
+        // many digits as a worry level can have. Part 1 levels have no
+        // bound, so a first run finds the largest.
+        let items: usize = parsed.iter().map(|monkey| monkey.items.len()).sum();
+        let largest = if lower_worry_level {
+            let mut largest = 0;
+            play(parsed, rounds, true, |_, monkeys| {
+                let levels = monkeys.iter().flat_map(|monkey| &monkey.items);
+                largest = levels.copied().fold(largest, u64::max);
+            })?;
+            parsed.iter().flat_map(|monkey| &monkey.items).copied().fold(largest, u64::max)
+        } else {
+            magic_trick(parsed)? - 1
+        };
+        let digits = largest.max(1).ilog10() as usize + 1;
+        let prefix = format!("Monkey {} (inspected {}): ", parsed.len(), rounds * items);
+        let width = prefix.len() + items * (digits + 2);
+
+        frame(Frame::from_lines("start", width, &draw(parsed)));
+        play(parsed, rounds, lower_worry_level, |round, monkeys| {
+            let caption = format!("round {}/{rounds}", round + 1);
+            frame(Frame::from_lines(caption, width, &draw(monkeys)));
+        })?;
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<String, Error> {
+    Day11::part1(&Day11::parse(&normalize(input))?)
+}
+
+pub fn part2(input: &str) -> Result<String, Error> {
+    Day11::part2(&Day11::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
@@ -254,13 +402,88 @@ Monkey 3:
 
     #[test]
     fn part1_works() {
-        let result = part1(INPUT);
+        let result = part1(INPUT).unwrap();
         assert_eq!(result, "10605");
     }
 
     #[test]
     fn part2_works() {
-        let result = part2(INPUT);
+        let result = part2(INPUT).unwrap();
         assert_eq!(result, "2713310158");
     }
+
+    #[test]
+    fn unknown_recipient_is_an_error() {
+        let input = INPUT.replace("throw to monkey 3", "throw to monkey 4");
+        let err = part1(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 11, line 1, column 1: monkey 0: there is no monkey 4 to throw to"
+        );
+    }
+
+    #[test]
+    fn part1_keeps_worry_levels_exact() {
+        // Reducing modulo 6 before dividing by three would give 19182.
+        let input = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old + 0
+  Test: divisible by 3
+    If true: throw to monkey 1
+    If false: throw to monkey 2
+
+Monkey 1:
+  Starting items: 54, 65, 75
+  Operation: new = old + 1
+  Test: divisible by 2
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 60, 97
+  Operation: new = old * 2
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+        assert_eq!(part1(input).unwrap(), "17030");
+    }
+
+    #[test]
+    fn part1_overflow_is_located() {
+        let input = "Monkey 0:
+  Starting items: 100
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 7
+  Operation: new = old * old
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+        let err = part1(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 11, line 8, column 1: monkey 1: a worry level overflows 64 bits in round 2"
+        );
+    }
+
+    #[test]
+    fn frames_show_every_round() {
+        let monkeys = Day11::parse(INPUT).unwrap();
+        let mut frames = vec![];
+        Day11::frames(&monkeys, 1, &mut |frame| frames.push(frame)).unwrap();
+        assert_eq!(frames.len(), 21);
+        assert_eq!(frames[1].caption, "round 1/20");
+        let first = frames[1].cells.row(0).collect::<String>();
+        assert_eq!(first.trim_end(), "Monkey 0 (inspected 2): 20, 23, 27, 26");
+    }
+
+    #[test]
+    fn inspection_counts_work() {
+        let monkeys = Day11::parse(INPUT).unwrap();
+        assert_eq!(inspection_counts(&monkeys, 20, true).unwrap(), [101, 95, 7, 105]);
+    }
 }
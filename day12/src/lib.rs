@@ -1,210 +1,205 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet};
+
+use aoc_common::{entry, finish, normalize, Error, Frame, Grid, ParseResult, Point, Solution, Visualize};
+use tracing::{debug_span, instrument};
 
 use nom::{
-    character::complete::{alpha1, newline},
+    branch::alt,
+    character::complete::{alpha1, line_ending, newline},
+    combinator::{eof, peek},
+    sequence::terminated,
     multi::separated_list1,
     *,
 };
 
-#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
-struct Node {
-    x: i32,
-    y: i32,
-    height: u8,
-}
-
-type Graph = HashMap<Node, Vec<Node>>;
+type Graph = HashMap<Point, Vec<Point>>;
 
-fn grid(input: &str) -> IResult<&str, Vec<Vec<char>>> {
+fn grid(input: &str) -> ParseResult<'_, Vec<Vec<char>>> {
+    let row = terminated(alpha1, peek(alt((line_ending, eof))));
     separated_list1(
         newline,
-        alpha1.map(|letters: &str| letters.chars().collect())
+        entry("expected a row of heights `a` to `z`, `S` or `E`", row.map(|letters: &str| letters.chars().collect()))
     )(input)
 }
 
-fn shortest_path(graph: &Graph, start: Node, goal: Node) -> Option<i32> {
-    let mut queue = VecDeque::new();
-    queue.push_back(start);
-
-    let mut path = HashMap::new();
-    path.insert(start, vec![]);
-
-
-    while let Some(node) = queue.pop_front() {
-        if node == goal {
-            return Some(path[&goal].clone().len() as i32);
+/// The fewest steps from the nearest of `sources` to the goal, if it can
+/// be reached at all.
+#[instrument(level = "trace", skip(graph, sources), fields(sources = sources.len()))]
+fn shortest_path(graph: &Graph, sources: &[Point], goal: Point) -> Option<i32> {
+    let mut steps = None;
+    explore(graph, sources, goal, |step, _, frontier| {
+        if frontier.contains(&goal) {
+            steps = Some(step as i32);
         }
+    });
+    steps
+}
 
-        for neighbor in &graph[&node] {
-            if !path.contains_key(neighbor) {
-                queue.push_back(*neighbor);
-                let mut new_path = path[&node].clone();
-                new_path.push(*neighbor);
-                path.insert(*neighbor, new_path);
-            }
-        }
+/// Explores the graph from all of `sources` at once, one step at a time,
+/// until the goal is reached or nothing new is in reach. `observe` sees
+/// the squares reached so far and the ones just reached after each step.
+fn explore(
+    graph: &Graph,
+    sources: &[Point],
+    goal: Point,
+    mut observe: impl FnMut(usize, &HashSet<Point>, &[Point]),
+) {
+    let mut reached: HashSet<Point> = sources.iter().copied().collect();
+    let mut frontier = sources.to_vec();
+    let mut steps = 0;
+    observe(steps, &reached, &frontier);
+
+    while !frontier.is_empty() && !frontier.contains(&goal) {
+        frontier = frontier
+            .iter()
+            .flat_map(|point| &graph[point])
+            .filter(|&&neighbor| reached.insert(neighbor))
+            .copied()
+            .collect();
+        steps += 1;
+        observe(steps, &reached, &frontier);
     }
-    None
 }
 
-pub fn part1(input: &str) -> String {
-    let (_, grid) = grid(input).unwrap();
+/// The climbing graph built from the heightmap, along with the start,
+/// the goal and every lowest square a hike could begin from.
+#[derive(Debug)]
+pub struct Heightmap {
+    map: Grid<char>,
+    graph: Graph,
+    start: Point,
+    goal: Point,
+    start_points: Vec<Point>,
+}
 
-    let mut start = Node { x: 0, y: 0, height: 1 };
-    let mut goal = Node { x: 0, y: 0, height: 26 };
+impl Heightmap {
+    /// The map as given, with `S` and `E` in place.
+    pub fn map(&self) -> &Grid<char> {
+        &self.map
+    }
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == 'S' {
-                start.x = i as i32;
-                start.y = j as i32;
-            } else if grid[i][j] == 'E' {
-                goal.x = i as i32;
-                goal.y = j as i32;
-            }
-        }
+    /// Where `S` is.
+    pub fn start(&self) -> Point {
+        self.start
     }
 
-    let grid = grid.iter()
-        .map(|line| {
-            line.iter()
-                .map(|c| {
-                    match c {
-                        'S' => 1,
-                        'E' => 26,
-                        _ => *c as u8 - 'a' as u8 + 1
-                    }
-                })
-                .collect::<Vec<u8>>()
-        })
-        .collect::<Vec<Vec<u8>>>();
-
-    let mut graph = Graph::new();
-    for i in 0i32..(grid.len() as i32) {
-        for j in 0i32..(grid[0].len() as i32) {
-            let curr_node = Node {
-                x: i as i32,
-                y : j as i32,
-                height: grid[i as usize][j as usize]
-            };
-            let neighbors = vec![
-                (i + 1, j),
-                (i - 1, j),
-                (i, j + 1),
-                (i, j - 1),
-            ];
-            let edges = neighbors.iter()
-                .filter_map(|(x, y)| {
-                    grid.get(*x as usize)
-                        .and_then(|vec| {
-                            vec.get(*y as usize)
-                        })
-                        .and_then(|existing_cell| {
-                            if curr_node.height + 1 >= *existing_cell {
-                                Some(Node{
-                                    x: *x,
-                                    y: *y,
-                                    height: *existing_cell})
-                            } else {
-                                None
-                            }
-                        })
-                })
-                .collect::<Vec<_>>();
-            graph.insert(curr_node, edges);
-        }
+    /// Where `E` is.
+    pub fn goal(&self) -> Point {
+        self.goal
+    }
+
+    /// The fewest steps from `S` to `E`, if `E` can be reached at all.
+    pub fn steps(&self) -> Option<i32> {
+        shortest_path(&self.graph, &[self.start], self.goal)
     }
-    shortest_path(&graph, start, goal).unwrap().to_string()
 }
 
-pub fn part2(input: &str) -> String {
-    let (_, grid) = grid(input).unwrap();
+pub struct Day12;
 
-    let mut start_points = vec![];
-    let mut goal = Node { x: 0, y: 0, height: 26 };
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == 'S' || grid[i][j] == 'a' {
-                start_points.push(Node {
-                    x: i as i32,
-                    y: j as i32,
-                    height: 1
-                })
-            } else if grid[i][j] == 'E' {
-                goal.x = i as i32;
-                goal.y = j as i32;
+    type Parsed<'a> = Heightmap;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        let grid = Grid::from_rows(Self::DAY, finish(Self::DAY, input, grid(input))?)?;
+
+        for (point, &c) in grid.iter() {
+            if !matches!(c, 'S' | 'E' | 'a'..='z') {
+                return Err(Error::new(Self::DAY, format!("invalid height {c:?}"))
+                    .at(point.y as usize + 1, point.x as usize + 1));
             }
         }
-    }
+        let start = grid
+            .position(|&c| c == 'S')
+            .ok_or_else(|| Error::new(Self::DAY, "the heightmap has no start `S`"))?;
+        let goal = grid
+            .position(|&c| c == 'E')
+            .ok_or_else(|| Error::new(Self::DAY, "the heightmap has no goal `E`"))?;
+        let start_points = grid
+            .iter()
+            .filter(|(_, &c)| c == 'S' || c == 'a')
+            .map(|(point, _)| point)
+            .collect();
 
-    let grid = grid.iter()
-        .map(|line| {
-            line.iter()
-                .map(|c| {
-                    match c {
-                        'S' => 1,
-                        'E' => 26,
-                        _ => *c as u8 - 'a' as u8 + 1
-                    }
-                })
-                .collect::<Vec<u8>>()
-        })
-        .collect::<Vec<Vec<u8>>>();
-
-    let mut graph = Graph::new();
-    for i in 0i32..(grid.len() as i32) {
-        for j in 0i32..(grid[0].len() as i32) {
-            let curr_node = Node {
-                x: i as i32,
-                y : j as i32,
-                height: grid[i as usize][j as usize]
-            };
-            let neighbors = vec![
-                (i + 1, j),
-                (i - 1, j),
-                (i, j + 1),
-                (i, j - 1),
-            ];
-            let edges = neighbors.iter()
-                .filter_map(|(x, y)| {
-                    grid.get(*x as usize)
-                        .and_then(|vec| {
-                            vec.get(*y as usize)
-                        })
-                        .and_then(|existing_cell| {
-                            if curr_node.height + 1 >= *existing_cell {
-                                Some(Node{
-                                    x: *x,
-                                    y: *y,
-                                    height: *existing_cell})
-                            } else {
-                                None
-                            }
-                        })
+        let heights = grid.map(|&c| {
+            match c {
+                'S' => 1,
+                'E' => 26,
+                _ => c as u8 - b'a' + 1
+            }
+        });
+
+        let span = debug_span!("build_graph", width = heights.width(), height = heights.height());
+        let graph = span.in_scope(|| {
+            heights.points()
+                .map(|point| {
+                    let edges = heights.neighbours4(point)
+                        .filter(|&neighbor| heights[point] + 1 >= heights[neighbor])
+                        .collect::<Vec<_>>();
+                    (point, edges)
                 })
-                .collect::<Vec<_>>();
-            graph.insert(curr_node, edges);
-        }
+                .collect()
+        });
+
+        Ok(Heightmap { map: grid, graph, start, goal, start_points })
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        parsed
+            .steps()
+            .map(|steps| steps.to_string())
+            .ok_or_else(|| Error::new(Self::DAY, "there is no path from `S` to `E`"))
     }
 
-    start_points.iter()
-        .map(|point| {
-            let dist = shortest_path(&graph, *point, goal);
-            if dist.is_some() {
-                dist.unwrap()
-            } else {
-                9999
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        shortest_path(&parsed.graph, &parsed.start_points, parsed.goal)
+            .map(|steps| steps.to_string())
+            .ok_or_else(|| Error::new(Self::DAY, "there is no path from any `a` to `E`"))
+    }
+}
+
+impl Visualize for Day12 {
+    /// Floods the map outwards from `S`, or from every lowest square in
+    /// part 2: `.` has been reached, `@` was reached on the latest step.
+    fn frames(
+        parsed: &Self::Parsed<'_>,
+        part: u8,
+        frame: &mut dyn FnMut(Frame),
+    ) -> Result<(), Error> {
+        let sources = if part == 1 {
+            vec![parsed.start]
+        } else {
+            parsed.start_points.clone()
+        };
+        explore(&parsed.graph, &sources, parsed.goal, |steps, reached, frontier| {
+            let mut cells = parsed.map.clone();
+            for &point in reached {
+                cells[point] = '.';
             }
-        })
-        .min()
-        .unwrap()
-        .to_string()
+            for &point in frontier {
+                cells[point] = '@';
+            }
+            cells[parsed.goal] = 'E';
+            let caption = format!("step {steps}, {} squares reached", reached.len());
+            frame(Frame { caption, cells });
+        });
+        Ok(())
+    }
+}
+
+pub fn part1(input: &str) -> Result<String, Error> {
+    Day12::part1(&Day12::parse(&normalize(input))?)
+}
+
+pub fn part2(input: &str) -> Result<String, Error> {
+    Day12::part2(&Day12::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "Sabqponm
 abcryxxl
@@ -214,13 +209,76 @@ abdefghi";
 
     #[test]
     fn part1_works() {
-        let result = part1(INPUT);
+        let result = part1(INPUT).unwrap();
         assert_eq!(result, "31");
     }
 
     #[test]
     fn part2_works() {
-        let result = part2(INPUT);
+        let result = part2(INPUT).unwrap();
         assert_eq!(result, "29");
     }
+
+    #[test]
+    fn broken_row_is_located() {
+        let err = part1("Sabqponm\nabcryxxl\naccszE!k").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 12, line 3, column 7: expected a row of heights `a` to `z`, `S` or `E`"
+        );
+    }
+
+    #[test]
+    fn frames_stop_at_the_goal() {
+        let heightmap = Day12::parse(INPUT).unwrap();
+        for (part, steps) in [(1, 31), (2, 29)] {
+            let mut frames = vec![];
+            Day12::frames(&heightmap, part, &mut |frame| frames.push(frame)).unwrap();
+            assert_eq!(frames.len(), steps + 1);
+            assert_eq!(frames[0].cells.width(), 8);
+        }
+    }
+
+    /// A small heightmap with `S` and `E` on distinct squares, along with
+    /// the Manhattan distance between them. A ramp snaking row by row from
+    /// `S` climbs one letter a step up to `y` beside `E`, so `E` can always
+    /// be reached; every other square has a random height.
+    fn heightmaps() -> impl Strategy<Value = (String, usize)> {
+        (6usize..12, 5usize..12).prop_flat_map(|(width, height)| {
+            let cells = width * height;
+            let heights = prop::collection::vec(prop::char::range('a', 'z'), cells);
+            (heights, 0..cells - 26, any::<bool>()).prop_flat_map(move |(heights, low, flip)| {
+                (Just(heights), Just(low), low + 26..cells, Just(flip))
+            }).prop_map(move |(mut squares, low, high, flip)| {
+                // The square `i` squares along the snake.
+                let snake = |i: usize| {
+                    let row = i / width;
+                    row * width + if row.is_multiple_of(2) { i % width } else { width - 1 - i % width }
+                };
+                let (start, goal) = if flip { (high, low) } else { (low, high) };
+                for i in low..=high {
+                    let to_goal = i.abs_diff(goal).min(25) as u8;
+                    squares[snake(i)] = (b'z' - to_goal) as char;
+                }
+                let (start, goal) = (snake(start), snake(goal));
+                squares[start] = 'S';
+                squares[goal] = 'E';
+                let map = squares
+                    .chunks(width)
+                    .map(|row| row.iter().collect::<String>())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let distance = (start % width).abs_diff(goal % width) + (start / width).abs_diff(goal / width);
+                (map, distance)
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn paths_are_at_least_the_manhattan_distance((input, distance) in heightmaps()) {
+            let steps = part1(&input).unwrap();
+            prop_assert!(steps.parse::<usize>().unwrap() >= distance);
+        }
+    }
 }
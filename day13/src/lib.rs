@@ -1,23 +1,25 @@
-#![feature(iter_intersperse)]
-
 use nom::{
-    branch::alt,
     multi::separated_list0,
     multi::separated_list1,
-    *, bytes::complete::tag, sequence::{delimited, separated_pair}, character::complete::newline,
+    combinator::{cut, fail},
+    error::context,
+    *, bytes::complete::tag, sequence::separated_pair, character::complete::newline,
 };
-use std::{cmp::Ordering, vec, fmt::Display};
+use std::{cmp::Ordering, vec, fmt::Display, str::FromStr};
+
+use aoc_common::{entry, finish, normalize, Error, ParseResult, Solution};
+use tracing::{debug, debug_span};
 
 #[derive(Debug, Eq)]
-enum Packet {
+pub enum Packet {
     Integer(u32),
     List(Vec<Packet>),
 }
 
 #[derive(Debug)]
-struct Pair {
-    left: Packet,
-    right: Packet
+pub struct Pair {
+    pub left: Packet,
+    pub right: Packet
 }
 
 impl Display for Packet {
@@ -30,8 +32,8 @@ impl Display for Packet {
                     "[{}]",
                     l1.iter()
                       .map(|v| v.to_string())
-                      .intersperse(",".to_string())
-                      .collect::<String>()
+                      .collect::<Vec<String>>()
+                      .join(",")
                 ),
                 Packet::Integer(n) => n.to_string()
 
@@ -62,84 +64,127 @@ impl Ord for Packet {
         match (self, other) {
             (Packet::Integer(n1), Packet::Integer(n2)) => n1.cmp(n2),
             (Packet::List(l1), Packet::Integer(n2)) => l1.cmp(&vec![Packet::Integer(*n2)]),
-            (Packet::Integer(n1), Packet::List(l2)) => vec![Packet::Integer(*n1)].cmp(&l2),
+            (Packet::Integer(n1), Packet::List(l2)) => vec![Packet::Integer(*n1)].cmp(l2),
             (Packet::List(l1), Packet::List(l2)) => l1.cmp(l2)
         }
     }
 }
 
-fn list(input: &str) -> IResult<&str, Packet> {
-    alt((
-        delimited(
-            tag("["),
-            separated_list0(tag(","), list),
-            tag("]"),
-        )
-        .map(|vec| Packet::List(vec)),
-        nom::character::complete::u32
-            .map(|num| Packet::Integer(num))
-    ))(input)
+/// [`MAX_DEPTH`] as a literal, so [`TOO_DEEP`] can spell it out.
+macro_rules! max_depth {
+    () => {
+        1024
+    };
+}
+
+/// How deeply lists may nest. Parsing, comparing and printing packets all
+/// recurse, so deeper input is rejected instead of overflowing the stack.
+pub const MAX_DEPTH: usize = max_depth!();
+
+/// The error for a list nested deeper than [`MAX_DEPTH`].
+const TOO_DEEP: &str = concat!("packets nest at most ", max_depth!(), " lists deep");
+
+fn list(input: &str) -> ParseResult<'_, Packet> {
+    nested_list(input, 0)
+}
+
+fn nested_list(input: &str, depth: usize) -> ParseResult<'_, Packet> {
+    let Some(rest) = input.strip_prefix('[') else {
+        return nom::character::complete::u32.map(Packet::Integer).parse(input);
+    };
+    if depth >= MAX_DEPTH {
+        return cut(context(TOO_DEEP, fail))(input);
+    }
+    // Written out rather than with `delimited` and `alt`, whose extra stack
+    // frames at every level would not fit `MAX_DEPTH` levels on a 2 MiB
+    // thread stack.
+    let (rest, items) = separated_list0(tag(","), |input| nested_list(input, depth + 1))(rest)?;
+    let (rest, _) = tag("]")(rest)?;
+    Ok((rest, Packet::List(items)))
 }
 
-fn pairs(input: &str) -> IResult<&str, Vec<Pair>> {
+impl FromStr for Packet {
+    type Err = Error;
+
+    /// Parses a single packet, such as `[1,[2,3]]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        finish(Day13::DAY, s, list(s))
+    }
+}
+
+fn pairs(input: &str) -> ParseResult<'_, Vec<Pair>> {
     separated_list1(
         tag("\n\n"),
-        separated_pair(list, newline, list).map(
+        entry("expected two packets like `[1,[2,3]]`, one per line", separated_pair(list, newline, list).map(
             |(p1, p2)| Pair { left: p1, right: p2 }
-        )
+        ))
     )(input)
 }
 
-pub fn part1(input: &str) -> String {
-    let (_input, pairs) = pairs(input).unwrap();
-    pairs.iter()
-        .enumerate()
-        .filter_map(|(i, Pair { left, right })| {
-            if left < right {
-                Some(i + 1)
-            } else {
-                None
-            }
-        })
-        .sum::<usize>()
-        .to_string()
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Parsed<'a> = Vec<Pair>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        finish(Self::DAY, input, pairs(input))
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(parsed.iter()
+            .enumerate()
+            .filter_map(|(i, Pair { left, right })| {
+                if left < right {
+                    Some(i + 1)
+                } else {
+                    None
+                }
+            })
+            .sum::<usize>()
+            .to_string())
+    }
 
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let packet_2 = Packet::List(vec![Packet::List(vec![Packet::Integer(2)])]);
+        let packet_6 = Packet::List(vec![Packet::List(vec![Packet::Integer(6)])]);
+
+        let mut packets = parsed.iter()
+            .flat_map(|Pair { left, right }| [left, right])
+            .chain([&packet_2, &packet_6])
+            .collect::<Vec<&Packet>>();
+        debug_span!("sort", packets = packets.len()).in_scope(|| packets.sort());
+        debug!(
+            "sorted packets:\n{}",
+            packets
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+        let divider_index = |divider: &Packet| {
+            packets.iter()
+                .position(|packet| *packet == divider)
+                .map(|i| i + 1)
+                .ok_or_else(|| Error::new(Self::DAY, format!("divider {divider} went missing")))
+        };
+        Ok((divider_index(&packet_2)? * divider_index(&packet_6)?).to_string())
+    }
+}
+
+pub fn part1(input: &str) -> Result<String, Error> {
+    Day13::part1(&Day13::parse(&normalize(input))?)
 }
 
-pub fn part2(input: &str) -> String {
-    let (_input, pairs) = pairs(input).unwrap();
-    let packet_2 = Packet::List(vec![Packet::List(vec![Packet::Integer(2)])]);
-    let packet_6 = Packet::List(vec![Packet::List(vec![Packet::Integer(6)])]);
-
-    let mut packets = pairs.iter()
-        .flat_map(|Pair { left, right }| [left, right])
-        .chain([&packet_2, &packet_6])
-        .collect::<Vec<&Packet>>();
-    packets.sort();
-    println!(
-        "{}",
-        &packets
-            .iter()
-            .map(|v| v.to_string())
-            .intersperse("\n".to_string())
-            .collect::<String>()
-    );
-    let packet_2_idx = packets.iter()
-        .enumerate()
-        .map(|(i, v)| (i + 1, v))
-        .find(|(_i, packet)| packet == &&&packet_2)
-        .unwrap();
-    let packet_6_idx = packets.iter()
-        .enumerate()
-        .map(|(i, v)| (i + 1, v))
-        .find(|(_i, packet)| packet == &&&packet_6)
-        .unwrap();
-    (packet_2_idx.0 * packet_6_idx.0).to_string()
+pub fn part2(input: &str) -> Result<String, Error> {
+    Day13::part2(&Day13::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "[1,1,3,1,1]
 [1,1,5,1,1]
@@ -167,14 +212,67 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = part1(INPUT);
+        let result = part1(INPUT).unwrap();
         assert_eq!(result, "13");
     }
 
     #[test]
     fn part2_works() {
-        let result = part2(INPUT);
+        let result = part2(INPUT).unwrap();
         assert_eq!(result, "140");
     }
 
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let deep = format!("{}{}\n[]", "[".repeat(100_000), "]".repeat(100_000));
+        let err = part1(&deep).unwrap_err();
+        assert_eq!(err.location.map(|l| l.column), Some(MAX_DEPTH + 1));
+        assert!(err.to_string().contains(&format!("at most {MAX_DEPTH} lists")), "{err}");
+    }
+
+    #[test]
+    fn deepest_packets_compare_and_print() {
+        let deep = |n| format!("{}{n}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        let left: Packet = deep(1).parse().unwrap();
+        let right: Packet = deep(2).parse().unwrap();
+        assert!(left < right);
+        assert_eq!(left.to_string(), deep(1));
+        assert_eq!(part1(&format!("{}\n{}", deep(1), deep(2))).unwrap(), "1");
+    }
+
+    #[test]
+    fn single_packets_parse_and_compare() {
+        let left: Packet = "[[1],[2,3,4]]".parse().unwrap();
+        let right: Packet = "[[1],4]".parse().unwrap();
+        assert!(left < right);
+        assert_eq!(left.to_string(), "[[1],[2,3,4]]");
+        assert!("[1,2".parse::<Packet>().is_err());
+    }
+
+    fn packets() -> impl Strategy<Value = Packet> {
+        let integer = (0u32..11).prop_map(Packet::Integer);
+        integer.prop_recursive(4, 32, 5, |packet| {
+            prop::collection::vec(packet, 0..5).prop_map(Packet::List)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn packet_order_is_total(a in packets(), b in packets(), c in packets()) {
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            prop_assert_eq!(a.cmp(&b) == Ordering::Equal, a == b);
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+            if a < b && b < c {
+                prop_assert!(a < c);
+            }
+        }
+
+        #[test]
+        fn packets_print_as_they_parse(packet in packets()) {
+            let reparsed: Packet = packet.to_string().parse().unwrap();
+            prop_assert_eq!(reparsed.cmp(&packet), Ordering::Equal);
+        }
+    }
 }
@@ -0,0 +1,56 @@
+use day2::{decode, Decoding};
+use aoc_common::load_input;
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+/// Writes the table of scores, then the readings that score `target`.
+fn print(decoding: &Decoding, target: Option<u64>) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    write!(out, "{decoding}")?;
+    if let Some(target) = target {
+        writeln!(out, "\nscoring {target}:")?;
+        for reading in decoding.matching(target) {
+            writeln!(out, "{}", decoding.describe(reading))?;
+        }
+    }
+    Ok(())
+}
+
+/// Scores ./input.txt under every reading of its second column, and lists
+/// the readings that score the target given as the first argument.
+fn main() -> ExitCode {
+    let target = match std::env::args().nth(1).map(|arg| arg.parse::<u64>()) {
+        None => None,
+        Some(Ok(target)) => Some(target),
+        Some(Err(err)) => {
+            eprintln!("the target must be a score: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = load_input("./input.txt")
+        .map_err(|err| format!("cannot read ./input.txt: {err}"))
+        .and_then(|file| decode(&file).map_err(|err| err.render(&file)));
+
+    match result {
+        Ok(decoding) => exit(print(&decoding, target)),
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Exits cleanly once the output is written, or once whatever reads it
+/// stops reading, as `head` does.
+fn exit(written: io::Result<()>) -> ExitCode {
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("cannot write the output: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt::Display;
+
+use aoc_common::{normalize, Error, Solution};
+
+use crate::{total_score, Day2, Game, Move, Outcome, Reading, Response, Rules};
+
+/// The most moves a game may have to be decoded: every ordering of its
+/// moves is tried, and there are already 5040 of seven.
+pub const MAX_DECODED_MOVES: usize = 7;
+
+/// The score a strategy guide gives under every way of reading its second
+/// column: as each assignment of the game's moves to the response symbols,
+/// then as losing, drawing and winning.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoding {
+    pub game: Game,
+    /// Every reading with its total score, in the order they were tried.
+    pub scores: Vec<(Reading, u64)>,
+}
+
+impl Decoding {
+    pub fn new(game: Game, guide: &[(Move, Response)]) -> Result<Self, Error> {
+        if game.len() > MAX_DECODED_MOVES {
+            return Err(Error::new(
+                Day2::DAY,
+                format!(
+                    "only games of up to {MAX_DECODED_MOVES} moves can be decoded, not {}",
+                    game.len()
+                ),
+            ));
+        }
+        let mut readings: Vec<Reading> = permutations(game.len())
+            .into_iter()
+            .map(|order| Reading::Moves(order.into_iter().map(Move).collect()))
+            .collect();
+        // Three outcomes only have a natural order with three responses.
+        if game.len() == 3 {
+            readings.push(Reading::Outcomes(vec![Outcome::Loss, Outcome::Draw, Outcome::Win]));
+        }
+        let scores = readings
+            .into_iter()
+            .map(|reading| {
+                let rules = Rules::new(game.clone(), reading.clone())?;
+                Ok((reading, total_score(&rules, guide)))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Decoding { game, scores })
+    }
+
+    /// The reading with the highest score, the first tried on a tie.
+    pub fn best(&self) -> Option<&(Reading, u64)> {
+        self.scores
+            .iter()
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+    }
+
+    /// Every reading that scores exactly `target`.
+    pub fn matching(&self, target: u64) -> impl Iterator<Item = &Reading> {
+        self.scores
+            .iter()
+            .filter(move |(_, score)| *score == target)
+            .map(|(reading, _)| reading)
+    }
+
+    /// `reading` as `X=Rock Y=Paper Z=Scissors`.
+    pub fn describe(&self, reading: &Reading) -> String {
+        let meaning = |i: usize| match reading {
+            Reading::Moves(moves) => self.game.name(moves[i]).to_string(),
+            Reading::Outcomes(outcomes) => format!("{:?}", outcomes[i]),
+        };
+        (0..self.game.len())
+            .map(|i| format!("{}={}", self.game.symbol(Response(i)), meaning(i)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Every ordering of `0..n`, in lexicographic order.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    (0..n)
+        .flat_map(|first| {
+            permutations(n - 1).into_iter().map(move |rest| {
+                let mut order = vec![first];
+                order.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+                order
+            })
+        })
+        .collect()
+}
+
+impl Display for Decoding {
+    /// One line per reading, marking the one [`Decoding::best`] picks and
+    /// whether others tie with it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let best = self.best();
+        let tied = best.is_some_and(|(_, top)| self.matching(*top).nth(1).is_some());
+        for entry @ (reading, score) in &self.scores {
+            let marker = match best {
+                Some(best) if std::ptr::eq(best, entry) && tied => "  <- best (tied)",
+                Some(best) if std::ptr::eq(best, entry) => "  <- best",
+                _ => "",
+            };
+            writeln!(f, "{:<40} {score:>8}{marker}", self.describe(reading))?;
+        }
+        Ok(())
+    }
+}
+
+/// Scores the puzzle input's strategy guide under every reading.
+pub fn decode(input: &str) -> Result<Decoding, Error> {
+    Decoding::new(Game::rock_paper_scissors(), &Day2::parse(&normalize(input))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MoveSpec;
+
+    const INPUT: &str = "A Y\nB X\nC Z";
+
+    #[test]
+    fn scores_every_reading() {
+        let decoding = decode(INPUT).unwrap();
+        let scores: Vec<u64> = decoding.scores.iter().map(|(_, score)| *score).collect();
+        assert_eq!(scores, [15, 6, 15, 15, 15, 24, 12]);
+        let (best, score) = decoding.best().unwrap();
+        assert_eq!((decoding.describe(best).as_str(), *score), ("X=Scissors Y=Paper Z=Rock", 24));
+        assert_eq!(decoding.describe(Rules::part2().reading()), "X=Loss Y=Draw Z=Win");
+    }
+
+    #[test]
+    fn finds_readings_matching_a_target() {
+        let decoding = decode(INPUT).unwrap();
+        assert_eq!(decoding.matching(15).count(), 4);
+        assert_eq!(decoding.matching(12).collect::<Vec<_>>(), [Rules::part2().reading()]);
+        assert_eq!(decoding.matching(13).count(), 0);
+    }
+
+    #[test]
+    fn the_table_marks_only_the_best_reading() {
+        let table = decode(INPUT).unwrap().to_string();
+        let marked: Vec<&str> = table.lines().filter(|line| line.contains("<- best")).collect();
+        assert_eq!(marked.len(), 1);
+        assert!(marked[0].starts_with("X=Scissors Y=Paper Z=Rock") && !marked[0].contains("tied"));
+
+        let tied = Decoding::new(Game::rock_paper_scissors(), &[]).unwrap().to_string();
+        let marked: Vec<&str> = tied.lines().filter(|line| line.contains("<- best")).collect();
+        assert_eq!(marked, [tied.lines().next().unwrap()]);
+        assert!(marked[0].ends_with("<- best (tied)"));
+    }
+
+    #[test]
+    fn tries_every_order_of_a_bigger_game() {
+        let decoding = Decoding::new(Game::rock_paper_scissors_lizard_spock(), &[]).unwrap();
+        assert_eq!(decoding.scores.len(), 120);
+        assert!(decoding.scores.iter().all(|(reading, _)| matches!(reading, Reading::Moves(_))));
+    }
+
+    #[test]
+    fn rejects_games_too_big_to_decode() {
+        let moves = (0..MAX_DECODED_MOVES + 2)
+            .map(|i| MoveSpec::new(&format!("move {i}"), &format!("A{i}"), &format!("X{i}"), 1))
+            .collect();
+        let game = Game::new(moves, [0, 3, 6]).unwrap();
+        let err = Decoding::new(game, &[]).unwrap_err();
+        assert_eq!(err.to_string(), "day 2: only games of up to 7 moves can be decoded, not 9");
+    }
+}
@@ -0,0 +1,337 @@
+use std::fmt::Display;
+
+use aoc_common::{Error, Solution};
+
+use crate::Day2;
+
+/// How a round ends for us.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+/// A move of a [`Game`], by its place in the game's cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Move(pub usize);
+
+/// A symbol from the second column of the strategy guide, by its place
+/// among the game's response symbols. What it means is up to the
+/// [`Reading`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Response(pub usize);
+
+/// One move of a game: what it is called, how the strategy guide writes it
+/// in either column and how many points playing it is worth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveSpec {
+    pub name: String,
+    pub opponent: String,
+    pub response: String,
+    pub points: u64,
+}
+
+impl MoveSpec {
+    pub fn new(name: &str, opponent: &str, response: &str, points: u64) -> Self {
+        MoveSpec {
+            name: name.to_string(),
+            opponent: opponent.to_string(),
+            response: response.to_string(),
+            points,
+        }
+    }
+}
+
+/// A cyclic game like Rock Paper Scissors: an odd number of moves in a
+/// cycle, each beating the half of the others just before it and losing
+/// to the half just after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    moves: Vec<MoveSpec>,
+    /// What a loss, a draw and a win are worth, in that order, on top of
+    /// the points for the move played.
+    outcome_points: [u64; 3],
+}
+
+impl Game {
+    pub fn new(moves: Vec<MoveSpec>, outcome_points: [u64; 3]) -> Result<Self, Error> {
+        if moves.len().is_multiple_of(2) {
+            return Err(Error::new(
+                Day2::DAY,
+                format!("a cyclic game needs an odd number of moves, not {}", moves.len()),
+            ));
+        }
+        for (i, spec) in moves.iter().enumerate() {
+            let clash = moves[..i]
+                .iter()
+                .find(|other| other.opponent == spec.opponent || other.response == spec.response);
+            if let Some(other) = clash {
+                return Err(Error::new(
+                    Day2::DAY,
+                    format!("{} and {} share a symbol", other.name, spec.name),
+                ));
+            }
+        }
+        Ok(Game { moves, outcome_points })
+    }
+
+    /// The puzzle's game: `A`/`X` Rock, `B`/`Y` Paper and `C`/`Z`
+    /// Scissors, worth 1, 2 and 3, with 0, 3 and 6 for a loss, draw and win.
+    pub fn rock_paper_scissors() -> Self {
+        let moves = vec![
+            MoveSpec::new("Rock", "A", "X", 1),
+            MoveSpec::new("Paper", "B", "Y", 2),
+            MoveSpec::new("Scissors", "C", "Z", 3),
+        ];
+        Game { moves, outcome_points: [0, 3, 6] }
+    }
+
+    /// Rock Paper Scissors Lizard Spock, written `A` to `E` and `V` to `Z`
+    /// in the order Rock, Paper, Scissors, Lizard, Spock and worth 1 to 5,
+    /// scored like the puzzle's game.
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        // In cycle order, so that every move beats the two before it.
+        let moves = vec![
+            MoveSpec::new("Rock", "A", "V", 1),
+            MoveSpec::new("Spock", "E", "Z", 5),
+            MoveSpec::new("Paper", "B", "W", 2),
+            MoveSpec::new("Lizard", "D", "Y", 4),
+            MoveSpec::new("Scissors", "C", "X", 3),
+        ];
+        Game { moves, outcome_points: [0, 3, 6] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Every move, in cycle order.
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.len()).map(Move)
+    }
+
+    pub fn name(&self, m: Move) -> &str {
+        &self.moves[m.0].name
+    }
+
+    /// How the second column of the strategy guide writes `response`.
+    pub fn symbol(&self, response: Response) -> &str {
+        &self.moves[response.0].response
+    }
+
+    /// How a round where we play `ours` against `theirs` ends for us.
+    pub fn outcome(&self, ours: Move, theirs: Move) -> Outcome {
+        let ahead = (ours.0 + self.len() - theirs.0) % self.len();
+        if ahead == 0 {
+            Outcome::Draw
+        } else if ahead <= self.len() / 2 {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
+
+    /// The move that ends a round against `theirs` with `outcome`: the
+    /// nearest one after it in the cycle to win, before it to lose.
+    pub fn play(&self, theirs: Move, outcome: Outcome) -> Move {
+        let n = self.len();
+        match outcome {
+            Outcome::Loss => Move((theirs.0 + n - 1) % n),
+            Outcome::Draw => theirs,
+            Outcome::Win => Move((theirs.0 + 1) % n),
+        }
+    }
+
+    /// Our score for a round where we play `ours` against `theirs`.
+    pub fn score(&self, ours: Move, theirs: Move) -> u64 {
+        self.moves[ours.0].points + self.outcome_points[self.outcome(ours, theirs) as usize]
+    }
+
+    /// One line of the strategy guide, with errors located within the line.
+    pub fn round(&self, line: &str) -> Result<(Move, Response), Error> {
+        let (oponent_move, response) = line
+            .split_once(' ')
+            .ok_or_else(|| Error::new(Day2::DAY, "expected `<move> <response>`").at(1, 1))?;
+        let theirs = self
+            .moves
+            .iter()
+            .position(|spec| spec.opponent == oponent_move)
+            .ok_or_else(|| {
+                Error::new(Day2::DAY, format!("unknown move {oponent_move:?}"))
+                    .at_fragment(line, oponent_move)
+            })?;
+        let ours = self
+            .moves
+            .iter()
+            .position(|spec| spec.response == response)
+            .ok_or_else(|| {
+                Error::new(Day2::DAY, format!("unexpected response {response:?}"))
+                    .at_fragment(line, response)
+            })?;
+        Ok((Move(theirs), Response(ours)))
+    }
+}
+
+/// One round of a game: the move we play against theirs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Round<'a> {
+    pub game: &'a Game,
+    pub theirs: Move,
+    pub ours: Move,
+}
+
+impl Round<'_> {
+    /// How the round ends for us.
+    pub fn outcome(&self) -> Outcome {
+        self.game.outcome(self.ours, self.theirs)
+    }
+
+    /// Our score for the round.
+    pub fn score(&self) -> u64 {
+        self.game.score(self.ours, self.theirs)
+    }
+
+    /// The move that would have ended the round with `outcome` instead.
+    pub fn move_for_outcome(&self, outcome: Outcome) -> Move {
+        self.game.play(self.theirs, outcome)
+    }
+}
+
+impl Display for Round<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} against {}: {:?}, {} points",
+            self.game.name(self.ours),
+            self.game.name(self.theirs),
+            self.outcome(),
+            self.score()
+        )
+    }
+}
+
+/// What the second column of the strategy guide means.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reading {
+    /// Response `i` means playing `moves[i]`.
+    Moves(Vec<Move>),
+    /// Response `i` means ending the round with `outcomes[i]`.
+    Outcomes(Vec<Outcome>),
+}
+
+/// A game and a way to read the strategy guide for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub game: Game,
+    reading: Reading,
+}
+
+impl Rules {
+    pub fn new(game: Game, reading: Reading) -> Result<Self, Error> {
+        let meanings = match &reading {
+            Reading::Moves(moves) => {
+                if let Some(m) = moves.iter().find(|m| m.0 >= game.len()) {
+                    return Err(Error::new(Day2::DAY, format!("the game has no move {}", m.0)));
+                }
+                moves.len()
+            }
+            Reading::Outcomes(outcomes) => outcomes.len(),
+        };
+        if meanings != game.len() {
+            return Err(Error::new(
+                Day2::DAY,
+                format!("the reading gives {meanings} meanings for {} responses", game.len()),
+            ));
+        }
+        Ok(Rules { game, reading })
+    }
+
+    /// Part 1: `X`, `Y` and `Z` mean playing Rock, Paper and Scissors.
+    pub fn part1() -> Self {
+        let game = Game::rock_paper_scissors();
+        let reading = Reading::Moves(game.moves().collect());
+        Rules { game, reading }
+    }
+
+    /// Part 2: `X`, `Y` and `Z` mean losing, drawing and winning.
+    pub fn part2() -> Self {
+        let reading = Reading::Outcomes(vec![Outcome::Loss, Outcome::Draw, Outcome::Win]);
+        Rules { game: Game::rock_paper_scissors(), reading }
+    }
+
+    pub fn reading(&self) -> &Reading {
+        &self.reading
+    }
+
+    /// The round the strategy guide tells us to play against `theirs`.
+    pub fn round(&self, theirs: Move, response: Response) -> Round<'_> {
+        let ours = match &self.reading {
+            Reading::Moves(moves) => moves[response.0],
+            Reading::Outcomes(outcomes) => self.game.play(theirs, outcomes[response.0]),
+        };
+        Round { game: &self.game, theirs, ours }
+    }
+
+    /// Every round of the strategy guide, in order.
+    pub fn rounds<'a>(&'a self, guide: &'a [(Move, Response)]) -> impl Iterator<Item = Round<'a>> {
+        guide.iter().map(|&(theirs, response)| self.round(theirs, response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_move_beats_half_of_the_others() {
+        for game in [Game::rock_paper_scissors(), Game::rock_paper_scissors_lizard_spock()] {
+            for ours in game.moves() {
+                let wins = game.moves().filter(|&theirs| game.outcome(ours, theirs) == Outcome::Win);
+                assert_eq!(wins.count(), game.len() / 2);
+                for theirs in game.moves() {
+                    let outcome = game.outcome(ours, theirs);
+                    assert_eq!(game.outcome(game.play(theirs, outcome), theirs), outcome);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lizard_poisons_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let named = |name| game.moves().find(|&m| game.name(m) == name).unwrap();
+        let (lizard, spock, rock) = (named("Lizard"), named("Spock"), named("Rock"));
+        assert_eq!(game.outcome(lizard, spock), Outcome::Win);
+        assert_eq!(game.outcome(rock, lizard), Outcome::Win);
+        assert_eq!(game.score(spock, rock), 5 + 6);
+        assert_eq!(game.round("D Z").unwrap(), (lizard, Response(1)));
+    }
+
+    #[test]
+    fn rounds_break_down_the_guide() {
+        let rules = Rules::part2();
+        let guide = [(Move(0), Response(1)), (Move(1), Response(0)), (Move(2), Response(2))];
+        let rounds: Vec<Round> = rules.rounds(&guide).collect();
+        let outcomes: Vec<Outcome> = rounds.iter().map(Round::outcome).collect();
+        assert_eq!(outcomes, [Outcome::Draw, Outcome::Loss, Outcome::Win]);
+        assert_eq!(rounds.iter().map(Round::score).sum::<u64>(), 12);
+        assert_eq!(rounds[0].move_for_outcome(Outcome::Win), Move(1));
+        assert_eq!(rounds[2].to_string(), "Rock against Scissors: Win, 7 points");
+    }
+
+    #[test]
+    fn rejects_games_that_are_not_cyclic() {
+        let spec = |name: &str, symbol: &str| MoveSpec::new(name, symbol, symbol, 1);
+        let even = Game::new(vec![spec("Rock", "A"), spec("Paper", "B")], [0, 3, 6]);
+        assert_eq!(even.unwrap_err().message, "a cyclic game needs an odd number of moves, not 2");
+        let clash = Game::new(vec![spec("Rock", "A"), spec("Paper", "B"), spec("Stone", "A")], [0, 3, 6]);
+        assert_eq!(clash.unwrap_err().message, "Rock and Stone share a symbol");
+        let short = Rules::new(Game::rock_paper_scissors(), Reading::Outcomes(vec![Outcome::Win]));
+        assert!(short.is_err());
+    }
+}
@@ -1,93 +1,70 @@
-use std::{str::FromStr, cmp::Ordering};
+use std::io::BufRead;
 
-#[derive(PartialEq, Copy, Clone)]
-enum Move {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+use aoc_common::{for_each_line, normalize, Error, Solution};
+
+mod decode;
+mod game;
+
+pub use decode::{decode, Decoding, MAX_DECODED_MOVES};
+pub use game::{Game, Move, MoveSpec, Outcome, Reading, Response, Round, Rules};
+
+/// The total score for following the strategy guide under `rules`.
+fn total_score(rules: &Rules, guide: &[(Move, Response)]) -> u64 {
+    rules.rounds(guide).map(|round| round.score()).sum()
 }
 
-impl FromStr for Move {
-    type Err = String;
+/// [`total_score`] for a guide read one line at a time.
+fn stream_score(rules: &Rules, input: impl BufRead) -> Result<String, Error> {
+    let mut result = 0;
+    for_each_line(Day2::DAY, input, |number, line| {
+        let (theirs, response) = rules.game.round(line).map_err(|err| err.on_line(number))?;
+        result += rules.round(theirs, response).score();
+        Ok(())
+    })?;
+    Ok(result.to_string())
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" | "X" => Ok(Move::Rock),
-            "B" | "Y" => Ok(Move::Paper),
-            "C" | "Z" => Ok(Move::Scissors),
-            _ => Err("Unkown move".to_string())
-        }
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Parsed<'a> = Vec<(Move, Response)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        let game = Game::rock_paper_scissors();
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| game.round(line).map_err(|err| err.on_line(i + 1)))
+            .collect()
     }
-}
 
-impl PartialOrd for Move {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self == &Move::Scissors && other == &Move::Rock {
-            Some(Ordering::Less)
-        } else if self == &Move::Rock && other == &Move::Scissors {
-            Some(Ordering::Greater)
-        } else {
-            Some((*self as u8).cmp(&(*other as u8)))
-        }
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(total_score(&Rules::part1(), parsed).to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(total_score(&Rules::part2(), parsed).to_string())
     }
 }
 
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day2::part1(&Day2::parse(&normalize(input))?)
+}
+
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day2::part2(&Day2::parse(&normalize(input))?)
+}
 
-pub fn process_part1(input: &str) -> String {
-    let result: u32 = input
-        .lines()
-        .map(|line| {
-            let moves: Vec<Move> = line
-                .split(" ")
-                .map(|s| s.parse::<Move>().unwrap())
-                .collect();
-            match moves[0].partial_cmp(&moves[1]) {
-                Some(Ordering::Equal) => 3 + moves[1] as u32,
-                Some(Ordering::Less) => 6 + moves[1] as u32,
-                Some(Ordering::Greater) => 0 + moves[1] as u32,
-                None => panic!("Moves should be comparable")
-            }
-        })
-        .sum();
-    result.to_string()
+/// [`process_part1`] for an input read one line at a time.
+pub fn stream_part1(input: impl BufRead) -> Result<String, Error> {
+    stream_score(&Rules::part1(), input)
 }
 
-pub fn process_part2(input: &str) -> String {
-    let result: u32 = input
-        .lines()
-        .map(|line| {
-            let moves: Vec<&str> = 
-                line.split(" ")
-                .collect();
-            let oponent_move = moves[0].parse::<Move>().unwrap();
-            match moves[1] {
-                "X" => {
-                    let our_move = match oponent_move {
-                        Move::Rock => Move::Scissors,
-                        Move::Paper => Move::Rock,
-                        Move::Scissors => Move::Paper,
-                    };
-                    0 + our_move as u32
-                },
-                "Y" => {
-                    let our_move = oponent_move;
-                    3 + our_move as u32
-                },
-                "Z" => {
-                    let our_move = match oponent_move {
-                        Move::Rock => Move::Paper,
-                        Move::Paper => Move::Scissors,
-                        Move::Scissors => Move::Rock,
-                    };
-                    6 + our_move as u32
-                },
-                _ => {
-                    panic!("Unexpected response");
-                }
-            }
-        })
-        .sum();
-    result.to_string()
+/// [`process_part2`] for an input read one line at a time.
+pub fn stream_part2(input: impl BufRead) -> Result<String, Error> {
+    stream_score(&Rules::part2(), input)
 }
 
 #[cfg(test)]
@@ -100,13 +77,27 @@ C Z";
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "15");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "12");
     }
+
+    #[test]
+    fn unknown_move_is_located() {
+        let err = process_part1("A Y\nB W").unwrap_err();
+        assert_eq!(err.to_string(), "day 2, line 2, column 3: unexpected response \"W\"");
+    }
+
+    #[test]
+    fn streaming_matches_the_whole_input() {
+        for input in [INPUT, "", "A Y\r\nB X\r\n\r\n", "A Y\nB W", "A Y\nBX"] {
+            assert_eq!(stream_part1(input.as_bytes()), process_part1(input), "{input:?}");
+            assert_eq!(stream_part2(input.as_bytes()), process_part2(input), "{input:?}");
+        }
+    }
 }
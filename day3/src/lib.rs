@@ -1,57 +1,148 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, io::BufRead};
 use itertools::Itertools;
 
-pub fn process_part1(input: &str) -> String {
-    let letter_scores = ('a'..='z')
+use aoc_common::{for_each_line, normalize, Error, Solution};
+
+fn letter_scores() -> HashMap<char, usize> {
+    ('a'..='z')
         .chain('A'..='Z')
         .enumerate()
         .map(|(idx, c)| (c, idx + 1))
-        .collect::<HashMap<char, usize>>();
+        .collect::<HashMap<char, usize>>()
+}
 
-    let result: usize = input
-        .lines()
-        .map(|rucksack| {
-            let sack_len = rucksack.len() / 2;
-            let first_compartment: &str = &rucksack[0..sack_len];
-            let second_compartment: &str = &rucksack[sack_len..(sack_len * 2)];
+/// Checks one line is a rucksack, with errors located within the line.
+fn rucksack(line: &str) -> Result<&str, Error> {
+    if let Some((i, c)) = line
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_alphabetic())
+    {
+        Err(Error::new(Day3::DAY, format!("invalid item {c:?}")).at_fragment(line, &line[i..]))
+    } else if !line.len().is_multiple_of(2) {
+        Err(Error::new(Day3::DAY, "rucksack has an odd number of items").at(1, 1))
+    } else {
+        Ok(line)
+    }
+}
 
-            let common_char = first_compartment
-                .chars()
-                .find(|c| second_compartment.contains(*c))
-                .unwrap();
+/// The priority of the item both compartments of `rucksack` hold.
+fn shared_item(rucksack: &str, letter_scores: &HashMap<char, usize>) -> Result<usize, Error> {
+    let sack_len = rucksack.len() / 2;
+    let first_compartment: &str = &rucksack[0..sack_len];
+    let second_compartment: &str = &rucksack[sack_len..(sack_len * 2)];
 
-            letter_scores.get(&common_char).unwrap()
-        })
-        .sum::<usize>();
-    result.to_string()
+    let common_char = first_compartment
+        .chars()
+        .find(|c| second_compartment.contains(*c))
+        .ok_or_else(|| Error::new(Day3::DAY, "compartments share no item"))?;
+
+    Ok(letter_scores[&common_char])
 }
 
-pub fn process_part2(input: &str) -> String {
-    let letter_scores = ('a'..='z')
-        .chain('A'..='Z')
-        .enumerate()
-        .map(|(idx, c)| (c, idx + 1))
-        .collect::<HashMap<char, usize>>();
-    
-    let result: usize = input
-        .lines()
-        .chunks(3)
-        .into_iter()
-        .map(|chunk| {
-            let bags: Vec<&str> = chunk.collect();
-
-            let common_char = bags[0]
-                .chars()
-                .find(|c| {
-                    bags[1].contains(*c)
-                        && bags[2].contains(*c)
-                })
-                .unwrap();
-                
-            letter_scores.get(&common_char).unwrap()
+/// The priority of the badge every rucksack of a group of three holds.
+fn badge<S: AsRef<str>>(bags: &[S], letter_scores: &HashMap<char, usize>) -> Result<usize, Error> {
+    let [first, second, third] = bags else {
+        return Err(Error::new(Day3::DAY, "group has fewer than three elves"));
+    };
+    let common_char = first
+        .as_ref()
+        .chars()
+        .find(|c| {
+            second.as_ref().contains(*c)
+                && third.as_ref().contains(*c)
         })
-        .sum::<usize>();
-    result.to_string()
+        .ok_or_else(|| Error::new(Day3::DAY, "group shares no badge"))?;
+
+    Ok(letter_scores[&common_char])
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Parsed<'a> = Vec<&'a str>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| rucksack(line).map_err(|err| err.on_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let letter_scores = letter_scores();
+
+        let result: usize = parsed
+            .iter()
+            .enumerate()
+            .map(|(i, rucksack)| {
+                shared_item(rucksack, &letter_scores).map_err(|err| err.on_line(i + 1))
+            })
+            .sum::<Result<usize, Error>>()?;
+        Ok(result.to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let letter_scores = letter_scores();
+
+        let result: usize = parsed
+            .iter()
+            .chunks(3)
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let bags: Vec<&&str> = chunk.collect();
+                badge(&bags, &letter_scores).map_err(|err| err.on_line(i * 3 + 1))
+            })
+            .sum::<Result<usize, Error>>()?;
+        Ok(result.to_string())
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day3::part1(&Day3::parse(&normalize(input))?)
+}
+
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day3::part2(&Day3::parse(&normalize(input))?)
+}
+
+/// [`process_part1`] for an input read one line at a time.
+pub fn stream_part1(input: impl BufRead) -> Result<String, Error> {
+    let letter_scores = letter_scores();
+    let mut result = 0;
+    for_each_line(Day3::DAY, input, |number, line| {
+        result += rucksack(line)
+            .and_then(|rucksack| shared_item(rucksack, &letter_scores))
+            .map_err(|err| err.on_line(number))?;
+        Ok(())
+    })?;
+    Ok(result.to_string())
+}
+
+/// [`process_part2`] for an input read one line at a time, keeping only
+/// the group being read.
+pub fn stream_part2(input: impl BufRead) -> Result<String, Error> {
+    let letter_scores = letter_scores();
+    let mut result = 0;
+    let mut group: Vec<String> = vec![];
+    let mut lines = 0;
+    for_each_line(Day3::DAY, input, |number, line| {
+        group.push(rucksack(line).map_err(|err| err.on_line(number))?.to_string());
+        lines = number;
+        if group.len() == 3 {
+            result += badge(&group, &letter_scores).map_err(|err| err.on_line(number - 2))?;
+            group.clear();
+        }
+        Ok(())
+    })?;
+    if !group.is_empty() {
+        let first = lines + 1 - group.len();
+        badge(&group, &letter_scores).map_err(|err| err.on_line(first))?;
+    }
+    Ok(result.to_string())
 }
 
 #[cfg(test)]
@@ -67,13 +158,22 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "157");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "70");
     }
+
+    #[test]
+    fn streaming_matches_the_whole_input() {
+        let broken = format!("{INPUT}\nab1d\n");
+        for input in [INPUT, "", &INPUT[..INPUT.len() - 25], "abcde", "abcd", &broken] {
+            assert_eq!(stream_part1(input.as_bytes()), process_part1(input), "{input:?}");
+            assert_eq!(stream_part2(input.as_bytes()), process_part2(input), "{input:?}");
+        }
+    }
 }
@@ -1,17 +1,20 @@
-use std::ops::RangeInclusive;
+use std::{io::BufRead, ops::RangeInclusive};
+
+use aoc_common::{entry, finish, for_each_line, normalize, Error, ParseResult, Solution};
 
 use nom::{
     sequence::separated_pair,
     character::complete::{self, newline},
     bytes::complete::tag,
     multi::separated_list1,
-    *,
 };
 
 
+pub type Assignment = (RangeInclusive<u32>, RangeInclusive<u32>);
+
 fn sections (
     input: &str,
-) -> IResult<&str, RangeInclusive<u32>> {
+) -> ParseResult<'_, RangeInclusive<u32>> {
     let (input, (start, end)) = separated_pair(
         complete::u32,
         tag("-"),
@@ -23,7 +26,7 @@ fn sections (
 
 fn line (
     input: &str,
-) -> IResult<&str, (RangeInclusive<u32>, RangeInclusive<u32>)> {
+) -> ParseResult<'_, Assignment> {
     let (input, (start, end)) = 
         separated_pair(sections, tag(","), sections)(
             input
@@ -32,54 +35,100 @@ fn line (
     Ok((input, (start, end)))
 }
 
+fn assignment(input: &str) -> ParseResult<'_, Assignment> {
+    entry("expected `A-B,C-D`", line)(input)
+}
+
 fn section_assignments (
     input: &str,
-) -> IResult<&str, Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>> {
+) -> ParseResult<'_, Vec<Assignment>> {
     let (input, ranges) =
-        separated_list1(newline, line)(input)?;
+        separated_list1(newline, assignment)(input)?;
 
     Ok((input, ranges))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, assignments) =
-        section_assignments(input).unwrap();
-    let result = assignments
-        .iter()
-        .filter(|(range_a, range_b)| {
-            let a_contains_b = range_a.contains(range_b.start()) &&
-                range_a.contains(range_b.end());
-            let b_contains_a = range_b.contains(range_a.start()) &&
-                range_b.contains(range_a.end());
-
-            a_contains_b || b_contains_a
-        })
-        .count();
+/// Whether one range of the pair contains the other.
+fn contained((range_a, range_b): &Assignment) -> bool {
+    let a_contains_b = range_a.contains(range_b.start()) &&
+        range_a.contains(range_b.end());
+    let b_contains_a = range_b.contains(range_a.start()) &&
+        range_b.contains(range_a.end());
 
-    result.to_string()
+    a_contains_b || b_contains_a
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, assignments) =
-        section_assignments(input).unwrap();
-    let result = assignments
-        .iter()
-        .filter(|(range_a, range_b)| {
-            let a_overlaps_b = range_a.contains(range_b.start()) ||
-                range_a.contains(range_b.end());
-            let b_overlaps_a = range_b.contains(range_a.start()) ||
-                range_b.contains(range_a.end());
-
-            a_overlaps_b || b_overlaps_a
-        })
-        .count();
+/// Whether the two ranges of the pair share a section.
+fn overlapping((range_a, range_b): &Assignment) -> bool {
+    let a_overlaps_b = range_a.contains(range_b.start()) ||
+        range_a.contains(range_b.end());
+    let b_overlaps_a = range_b.contains(range_a.start()) ||
+        range_b.contains(range_a.end());
+
+    a_overlaps_b || b_overlaps_a
+}
+
+/// Counts the pairs in `input` that `matches`, one line at a time.
+fn stream_count(input: impl BufRead, matches: fn(&Assignment) -> bool) -> Result<String, Error> {
+    let mut result = 0usize;
+    for_each_line(Day4::DAY, input, |number, line| {
+        let pair = finish(Day4::DAY, line, assignment(line)).map_err(|err| err.on_line(number))?;
+        result += usize::from(matches(&pair));
+        Ok(())
+    })?;
+    Ok(result.to_string())
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Parsed<'a> = Vec<Assignment>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        // No lines means no pairs, as when streaming.
+        if input.is_empty() {
+            return Ok(vec![]);
+        }
+        finish(Self::DAY, input, section_assignments(input))
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let result = parsed.iter().filter(|pair| contained(pair)).count();
+
+        Ok(result.to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let result = parsed.iter().filter(|pair| overlapping(pair)).count();
 
-    result.to_string()
+        Ok(result.to_string())
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day4::part1(&Day4::parse(&normalize(input))?)
+}
+
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day4::part2(&Day4::parse(&normalize(input))?)
+}
+
+/// [`process_part1`] for an input read one line at a time.
+pub fn stream_part1(input: impl BufRead) -> Result<String, Error> {
+    stream_count(input, contained)
+}
+
+/// [`process_part2`] for an input read one line at a time.
+pub fn stream_part2(input: impl BufRead) -> Result<String, Error> {
+    stream_count(input, overlapping)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const INPUT: &str = "2-4,6-8
 2-3,4-5
@@ -90,13 +139,51 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "2");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "4");
     }
+
+    #[test]
+    fn streaming_matches_the_whole_input() {
+        for input in [INPUT, "", "\n\n", "2-4,6-8\r\n5-7,7-9\r\n\r\n", "2-4,6-8\n5-7;7-9"] {
+            assert_eq!(stream_part1(input.as_bytes()), process_part1(input), "{input:?}");
+            assert_eq!(stream_part2(input.as_bytes()), process_part2(input), "{input:?}");
+        }
+    }
+
+    /// A range of sections such as `3-7`, start first.
+    fn sections() -> impl Strategy<Value = String> {
+        (1u32..100, 0u32..20).prop_map(|(start, len)| format!("{start}-{}", start + len))
+    }
+
+    fn assignments() -> impl Strategy<Value = String> {
+        prop::collection::vec((sections(), sections()), 0..50).prop_map(|pairs| {
+            pairs
+                .iter()
+                .map(|(a, b)| format!("{a},{b}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn contained_pairs_also_overlap(input in assignments()) {
+            let contained: usize = process_part1(&input).unwrap().parse().unwrap();
+            let overlapping: usize = process_part2(&input).unwrap().parse().unwrap();
+            prop_assert!(contained <= overlapping);
+        }
+
+        #[test]
+        fn streaming_gives_the_same_answers(input in assignments()) {
+            prop_assert_eq!(stream_part1(input.as_bytes()), process_part1(&input));
+            prop_assert_eq!(stream_part2(input.as_bytes()), process_part2(&input));
+        }
+    }
 }
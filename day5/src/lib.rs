@@ -1,15 +1,18 @@
+use aoc_common::{entry, finish, normalize, Error, Frame, ParseResult, Solution, Visualize};
+use tracing::instrument;
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{
-        self, alpha1, digit1, multispace1, newline, space1,
+        self, alpha1, digit1, multispace1, newline, space0, space1,
     },
+    combinator::verify,
+    error::context,
     multi::{many1, separated_list1},
-    sequence::{delimited, preceded},
-    *,
+    sequence::{delimited, preceded, terminated},
 };
 
-fn parse_crate(input: &str) -> IResult<&str, Option<&str>>{
+fn parse_crate(input: &str) -> ParseResult<'_, Option<&str>>{
     let (input, c) = alt((
         tag("   "),
         delimited(
@@ -27,11 +30,15 @@ fn parse_crate(input: &str) -> IResult<&str, Option<&str>>{
     Ok((input, result))
 }
 
+/// One row of the drawing. Rows may stop short of the widest one, or trail
+/// a few spaces, as editors that trim lines leave them.
 fn line(
     input: &str
-) -> IResult<&str, Vec<Option<&str>>> {
-    let (input, result) = 
-        separated_list1(tag(" "), parse_crate)(input)?;
+) -> ParseResult<'_, Vec<Option<&str>>> {
+    let (input, result) = terminated(
+        separated_list1(tag(" "), parse_crate),
+        space0,
+    )(input)?;
     Ok((input, result))
 }
 
@@ -44,17 +51,19 @@ struct Move {
 
 fn crates(
     input: &str
-) -> IResult<&str, Vec<Vec<&str>>> {
+) -> ParseResult<'_, Vec<Vec<&str>>> {
     let (input, crates_horizontal) = 
         separated_list1(newline, line)(input)?;
-    let mut crates_vertical: Vec<Vec<Option<&str>>> = vec![];
+    let width = crates_horizontal
+        .iter()
+        .map(|row| row.len())
+        .max()
+        .unwrap_or(0);
+    let mut crates_vertical: Vec<Vec<Option<&str>>> = vec![vec![]; width];
 
-    for _ in 0..=crates_horizontal[0].len() {
-        crates_vertical.push(vec![]);
-    }
     for vec in crates_horizontal.iter().rev() {
         for (i, c) in vec.iter().enumerate() {
-            crates_vertical[i].push(c.clone());
+            crates_vertical[i].push(*c);
         }
     }
     let final_crates: Vec<Vec<&str>> = crates_vertical
@@ -66,13 +75,13 @@ fn crates(
 
 fn move_crate(
     input: &str
-) -> IResult<&str, Move> {
+) -> ParseResult<'_, Move> {
     let (input, _) = tag("move ")(input)?;
     let (input, number) = complete::u32(input)?;
     let (input, _) = tag(" from ")(input)?;
-    let (input, from) = complete::u32(input)?;
+    let (input, from) = verify(complete::u32, |n| *n > 0)(input)?;
     let (input, _) = tag(" to ")(input)?;
-    let (input, to) = complete::u32(input)?;
+    let (input, to) = verify(complete::u32, |n| *n > 0)(input)?;
 
     Ok((
         input,
@@ -86,71 +95,177 @@ fn move_crate(
 
 fn moves(
     input: &str
-) -> IResult<&str, Vec<Move>> {
-    let (input, moves) = separated_list1(newline, move_crate)(input)?;
+) -> ParseResult<'_, Vec<Move>> {
+    let (input, moves) =
+        separated_list1(newline, entry("expected `move N from A to B`", move_crate))(input)?;
     
     Ok((input, moves))
 }
 
 fn parse_input(
     input: &str
-) -> IResult<&str, (Vec<Vec<&str>>, Vec<Move>)> {
+) -> ParseResult<'_, (Vec<Vec<&str>>, Vec<Move>)> {
     let (input, crates) = crates(input)?;
-    let (input, _) = newline(input)?;
-    let (input, _number) = many1(preceded(space1, digit1))(input)?;
-    let (input, _) = multispace1(input)?;
+    // A row of the drawing that does not parse ends it early, so this is
+    // where a broken crate shows up.
+    let (input, _number) = context(
+        "expected a crate like `[A]`, three spaces or the stack numbers",
+        preceded(newline, many1(preceded(space1, digit1))),
+    )(input)?;
+    let (input, _) = context("expected a blank line before the moves", multispace1)(input)?;
     let (input, moves) = moves(input)?;
     
     Ok((input, (crates, moves)))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, (mut crates, moves)) = parse_input(input).unwrap();
+/// The starting crate stacks (bottom first) and the rearrangement steps.
+#[derive(Debug, Clone)]
+pub struct Procedure<'a> {
+    stacks: Vec<Vec<&'a str>>,
+    moves: Vec<Move>,
+    first_move_line: usize,
+}
+
+impl<'a> Procedure<'a> {
+    /// The crates on each stack, bottom first.
+    pub fn stacks(&self) -> &[Vec<&'a str>] {
+        &self.stacks
+    }
 
-    for Move {number, from, to} in moves.iter() {
-        let len = crates[*from as usize].len();
-        let drained = crates[*from as usize]
-            .drain((len - *number as usize)..)
-            .rev()
+    /// Each move as `(number, from, to)`, numbering the stacks from 1 as
+    /// the input does.
+    pub fn moves(&self) -> impl Iterator<Item = (u32, u32, u32)> + '_ {
+        self.moves.iter().map(|Move { number, from, to }| (*number, from + 1, to + 1))
+    }
+}
+
+/// Runs every move, lifting crates one at a time (`one_at_a_time`) or all
+/// at once, and returns the crates left on top of each stack. `observe`
+/// sees the stacks after each move, along with the move's index.
+#[instrument(level = "debug", skip(procedure, observe), fields(moves = procedure.moves.len()))]
+fn rearrange(
+    procedure: &Procedure,
+    one_at_a_time: bool,
+    mut observe: impl FnMut(usize, &[Vec<&str>]),
+) -> Result<String, Error> {
+    let mut crates = procedure.stacks.clone();
+
+    for (i, Move {number, from, to}) in procedure.moves.iter().enumerate() {
+        let move_error = |message: String| {
+            Error::new(Day5::DAY, message).on_line(procedure.first_move_line + i)
+        };
+        let (from, to, number) = (*from as usize, *to as usize, *number as usize);
+        if from >= crates.len() || to >= crates.len() {
+            return Err(move_error(format!(
+                "there are only {} stacks",
+                crates.len()
+            )));
+        }
+        let len = crates[from].len();
+        if number > len {
+            return Err(move_error(format!(
+                "cannot move {number} crates from a stack of {len}"
+            )));
+        }
+        let mut drained = crates[from]
+            .drain((len - number)..)
             .collect::<Vec<&str>>();
-        for c in drained.iter() {
-            crates[*to as usize].push(c);
+        if one_at_a_time {
+            drained.reverse();
         }
+        crates[to].extend(drained);
+        observe(i, &crates);
     }
 
-    let result: String = crates
+    Ok(crates
         .iter()
         .map(|v| match v.iter().last() {
             Some(c) => c,
             None => "",
         })
-        .collect();
-
-    result
+        .collect())
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, (mut crates, moves)) = parse_input(input).unwrap();
+pub struct Day5;
 
-    for Move {number, from, to} in moves.iter() {
-        let len = crates[*from as usize].len();
-        let drained = crates[*from as usize]
-            .drain((len - *number as usize)..)
-            .collect::<Vec<&str>>();
-        for c in drained.iter() {
-            crates[*to as usize].push(c);
-        }
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Parsed<'a> = Procedure<'a>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        let (stacks, moves) = finish(Self::DAY, input, parse_input(input))?;
+        let first_move_line = input
+            .lines()
+            .position(|line| line.starts_with("move"))
+            .map_or(1, |i| i + 1);
+        Ok(Procedure { stacks, moves, first_move_line })
     }
 
-    let result: String = crates
-        .iter()
-        .map(|v| match v.iter().last() {
-            Some(c) => c,
-            None => "",
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        rearrange(parsed, true, |_, _| {})
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        rearrange(parsed, false, |_, _| {})
+    }
+}
+
+/// Draws the stacks the way the puzzle input does, `height` crates tall.
+fn draw(stacks: &[Vec<&str>], height: usize) -> Vec<String> {
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(c) => format!("[{c}]"),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
         })
         .collect();
+    lines.push(
+        (1..=stacks.len())
+            .map(|label| format!(" {label} "))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    lines
+}
+
+impl Visualize for Day5 {
+    fn frames(
+        parsed: &Self::Parsed<'_>,
+        part: u8,
+        frame: &mut dyn FnMut(Frame),
+    ) -> Result<(), Error> {
+        // No stack can grow taller than every crate piled together.
+        let height = parsed.stacks.iter().map(Vec::len).sum();
+        let width = draw(&parsed.stacks, 0)[0].len();
+        frame(Frame::from_lines("start", width, &draw(&parsed.stacks, height)));
+        rearrange(parsed, part == 1, |i, stacks| {
+            let Move { number, from, to } = parsed.moves[i];
+            let caption = format!(
+                "move {number} from {} to {} ({}/{})",
+                from + 1,
+                to + 1,
+                i + 1,
+                parsed.moves.len()
+            );
+            frame(Frame::from_lines(caption, width, &draw(stacks, height)));
+        })?;
+        Ok(())
+    }
+}
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day5::part1(&Day5::parse(&normalize(input))?)
+}
 
-    result
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day5::part2(&Day5::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
@@ -169,13 +284,45 @@ move 1 from 1 to 2";
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "CMZ");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "MCD");
     }
+
+    #[test]
+    fn impossible_move_is_an_error() {
+        let input = INPUT.replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        let err = process_part1(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 5, line 7, column 1: cannot move 4 crates from a stack of 3"
+        );
+    }
+
+    #[test]
+    fn trimmed_and_windows_drawings_parse() {
+        let trimmed = INPUT
+            .lines()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(process_part1(&trimmed).unwrap(), "CMZ");
+        let windows = INPUT.replace('\n', "\r\n").replace("[D]    ", "[D]  ");
+        assert_eq!(process_part2(&windows).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn frames_follow_the_moves() {
+        let procedure = Day5::parse(INPUT).unwrap();
+        let mut frames = vec![];
+        Day5::frames(&procedure, 2, &mut |frame| frames.push(frame)).unwrap();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[4].caption, "move 1 from 1 to 2 (4/4)");
+        assert!(frames[4].cells.to_string().ends_with("[Z]\n[M] [C] [P]\n 1   2   3 \n"));
+    }
 }
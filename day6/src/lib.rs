@@ -1,69 +1,82 @@
 use std::collections::BTreeSet;
 
-pub fn process_part1(input: &str) -> String {
-    let line: Vec<char> = input
-        .chars()
-        .collect::<Vec<char>>();
-
-    let mut result: u32 = 0;
-
-    for (i, window) in line.windows(4).enumerate() {
-        let set = window.iter().collect::<BTreeSet<&char>>();
-        if set.len() == 4 {
-            result = i as u32 + 4;
-            break;
-        }
-
-    }
-    result.to_string()
-}
+use aoc_common::{normalize, Error, Solution};
 
-pub fn process_part2(input: &str) -> String {
-    let window_size = 14;
-    let line: Vec<char> = input
-        .chars()
-        .collect::<Vec<char>>();
-
-    let sequence = line
+fn find_marker(signal: &[char], window_size: usize) -> Result<usize, Error> {
+    signal
         .windows(window_size)
         .enumerate()
         .find(|(_, slice)| {
             let set = slice.iter().collect::<BTreeSet<&char>>();
             slice.len() == set.len()
         })
-        .unwrap();
+        .map(|(i, _)| i + window_size)
+        .ok_or_else(|| {
+            Error::new(
+                Day6::DAY,
+                format!("no {window_size} distinct characters in a row"),
+            )
+        })
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Parsed<'a> = Vec<char>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        // The line ending is not part of the datastream.
+        Ok(input.strip_suffix('\n').unwrap_or(input).chars().collect())
+    }
+
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(find_marker(parsed, 4)?.to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(find_marker(parsed, 14)?.to_string())
+    }
+}
 
-    (sequence.0 + window_size).to_string()
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day6::part1(&Day6::parse(&normalize(input))?)
+}
+
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day6::part2(&Day6::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn part1_works() {
         assert_eq!(
-            process_part1("mjqjpqmgbljsphdztnvjfqwrcgsmlb"),
+            process_part1("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap(),
             "7"
         );
         assert_eq!(
-            process_part1("bvwbjplbgvbhsrlpgdmjqwftvncz"),
+            process_part1("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap(),
             "5"
         );
         assert_eq!(
-            process_part1("nppdvjthqldpwncqszvftbrmjlhg"),
+            process_part1("nppdvjthqldpwncqszvftbrmjlhg").unwrap(),
             "6"
         );
         assert_eq!(
             process_part1(
                 "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"
-            ),
+            ).unwrap(),
             "10"
         );
         assert_eq!(
             process_part1(
                 "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"
-            ),
+            ).unwrap(),
             "11"
         );
     }
@@ -71,25 +84,52 @@ mod tests {
     #[test]
     fn part2_works() {
         assert_eq!(
-            process_part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"),
+            process_part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap(),
             "19"
         );
         assert_eq!(
-            process_part2("bvwbjplbgvbhsrlpgdmjqwftvncz"),
+            process_part2("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap(),
             "23"
         );
         assert_eq!(
-            process_part2("nppdvjthqldpwncqszvftbrmjlhg"),
+            process_part2("nppdvjthqldpwncqszvftbrmjlhg").unwrap(),
             "23"
         );
         assert_eq!(
-            process_part2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"),
+            process_part2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg").unwrap(),
             "29"
         );
         assert_eq!(
-            process_part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"),
+            process_part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw").unwrap(),
             "26"
         );
     }
 
+    #[test]
+    fn line_ending_is_not_a_marker_character() {
+        assert_eq!(process_part1("abca\n").unwrap_err().to_string(), "day 6: no 4 distinct characters in a row");
+        assert_eq!(process_part1("aabcd\r\n").unwrap(), "5");
+    }
+
+    fn distinct(window: &[char]) -> bool {
+        window.iter().collect::<BTreeSet<_>>().len() == window.len()
+    }
+
+    proptest! {
+        // Fewer letters than the alphabet, so both finding and missing a
+        // 14 character marker come up.
+        #[test]
+        fn markers_end_the_first_distinct_window(signal in "[a-p]{0,100}") {
+            let signal: Vec<char> = signal.chars().collect();
+            for size in [4, 14] {
+                match find_marker(&signal, size) {
+                    Ok(end) => {
+                        prop_assert!(distinct(&signal[end - size..end]));
+                        prop_assert!(!signal[..end - 1].windows(size).any(distinct));
+                    }
+                    Err(_) => prop_assert!(!signal.windows(size).any(distinct)),
+                }
+            }
+        }
+    }
 }
@@ -1,69 +1,69 @@
 use std::collections::BTreeMap;
 
+use aoc_common::{entry, finish, normalize, Error, ParseResult, Solution};
+
+use tracing::{debug, instrument};
+
 use nom::{
     branch::alt,
     bytes::complete::{is_a, tag},
-    character::{
-        complete::{alpha1, anychar, newline, one_of},
-        is_alphabetic,
-    },
-    multi::{many1, separated_list1},
-    sequence::separated_pair,
-    *,
+    combinator::not,
+    character::complete::{alpha1, newline},
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
 };
 
-#[derive(Debug)]
-struct File<'a> {
-    size: u32,
-    name: &'a str,
+/// One line of an `ls` listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Files<'a> {
+    File { size: u64, name: &'a str },
+    Dir(&'a str),
 }
 
-#[derive(Debug)]
-enum Files<'a> {
-    File {size: u32, name: &'a str},
-    Dir(&'a str)
-}
-
-#[derive(Debug)]
-enum Operation<'a> {
+/// A command from the terminal output, with what `ls` listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation<'a> {
     Cd(Cd<'a>),
-    Ls(Vec<Files<'a>>)
+    Ls(Vec<Files<'a>>),
 }
 
-#[derive(Debug)]
-enum Cd<'a> {
+/// Where a `cd` goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cd<'a> {
     Root,
     Up,
     Down(&'a str),
 }
 
-fn file(input: &str) -> IResult<&str, Files> {
+fn file(input: &str) -> ParseResult<'_, Files<'_>> {
     let (input, (size, name)) = separated_pair(
-        nom::character::complete::u32,
+        nom::character::complete::u64,
         tag(" "),
         is_a("qwertyuiopasdfghjklzxcvbnm.")
     )(input)?;
 
-    Ok((input, Files::File {size, name}))
+    Ok((input, Files::File { size, name }))
 }
 
-fn directory(input: &str) -> IResult<&str, Files> {
+fn directory(input: &str) -> ParseResult<'_, Files<'_>> {
     let (input, _) = tag("dir ")(input)?;
     let (input, name) = alpha1(input)?;
     Ok((input, Files::Dir(name)))
 }
 
-fn ls(input: &str) -> IResult<&str, Operation> {
+fn ls(input: &str) -> ParseResult<'_, Operation<'_>> {
     let (input, _) = tag("$ ls")(input)?;
     let (input, _) = newline(input)?;
-    let (input, files) = separated_list1(newline, alt((
-        file, directory
-    )))(input)?;
+    // The listing goes on until the next command.
+    let (input, files) = separated_list1(newline, preceded(
+        not(tag("$")),
+        entry("expected `<size> <name>` or `dir <name>`", alt((file, directory))),
+    ))(input)?;
 
     Ok((input, Operation::Ls(files)))
 }
 
-fn cd(input: &str) -> IResult<&str, Operation> {
+fn cd(input: &str) -> ParseResult<'_, Operation<'_>> {
     let (input, _cd) = tag("$ cd ")(input)?;
     let (input, dir) = alt((tag(".."), alpha1, tag("/")))(input)?;
 
@@ -76,175 +76,115 @@ fn cd(input: &str) -> IResult<&str, Operation> {
     Ok((input, op))
 }
 
-fn commands(input: &str) -> IResult<&str, Vec<Operation>> {
+fn commands(input: &str) -> ParseResult<'_, Vec<Operation<'_>>> {
     let (input, cmd) =
-        separated_list1(newline, alt((ls, cd)))(input)?;
+        separated_list1(newline, entry("expected `$ cd <directory>` or `$ ls`", alt((ls, cd))))(input)?;
 
     Ok((input, cmd))
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_input, cmds) = commands(input).unwrap();
-    let mut directories: BTreeMap<String, Vec<File>> = BTreeMap::new();
-    let mut context: Vec<String> = vec![]; 
+/// Total size of every directory, keyed by its path: the root directory
+/// is the empty string and the others look like `/a/e`.
+pub type DirectorySizes = BTreeMap<String, u64>;
+
+/// Walks the commands and adds up the size of every directory listed.
+#[instrument(level = "debug", skip_all, fields(commands = cmds.len()))]
+pub fn directory_sizes(cmds: &[Operation]) -> Result<DirectorySizes, Error> {
+    let overflow = || Error::new(Day7::DAY, "directory sizes overflow 64 bits");
+    // The size of the files directly in each listed directory.
+    let mut directories: BTreeMap<String, u64> = BTreeMap::new();
+    let mut context: Vec<&str> = vec![];
 
-    for cmd in cmds.iter() {
+    for cmd in cmds {
         match cmd {
             Operation::Cd(Cd::Root) => {
-                context.push("".to_string());
+                context.clear();
             },
             Operation::Cd(Cd::Up) => {
                 context.pop();
             },
             Operation::Cd(Cd::Down(name)) => {
-                context.push(name.to_string());
+                context.push(name);
             },
             Operation::Ls(files) => {
-                directories
-                    .entry(
-                        context
-                            .iter()
-                            .cloned()
-                            .collect::<Vec<String>>()
-                            .join("/")
-                    )
-                    .or_insert(vec![]);
-                for file in files.iter() {
-                    match file {
-                        Files::File { size, name } => {
-                            directories.entry(
-                                context
-                                    .iter()
-                                    .cloned()
-                                    .collect::<Vec<String>>()
-                                    .join("/")
-                                )
-                                .and_modify(|vec| {
-                                    vec.push(File {
-                                        size: *size,
-                                        name
-                                    })
-                                });
-                        }
-                        Files::Dir(_) => (),
-                    }
-                }
+                let total = files.iter().try_fold(0u64, |total, file| match file {
+                    Files::File { size, .. } => total.checked_add(*size),
+                    Files::Dir(_) => Some(total),
+                });
+                // Listing a directory again lists the same files.
+                let path = context.iter().map(|name| format!("/{name}")).collect();
+                directories.insert(path, total.ok_or_else(overflow)?);
             },
         };
     }
 
-    let mut sizes: BTreeMap<String, u32> = BTreeMap::new();
-    for (path, files) in directories.iter() {
+    let mut sizes: BTreeMap<String, u64> = BTreeMap::new();
+    for (path, size) in directories.iter() {
         let dirs = path.split("/").collect::<Vec<&str>>();
-        let size = files
-            .iter()
-            .map(|File {size, ..} | size)
-            .sum::<u32>();
         for i in 0..dirs.len() {
-            sizes
+            let total = sizes
                 .entry(
-                    (&dirs[0..=i])
-                        .iter()
-                        .cloned()
-                        .collect::<Vec<&str>>()
-                        .join("/")
+                    dirs[0..=i].join("/")
                 )
-                .and_modify(|v| *v += size)
-                .or_insert(size);
+                .or_insert(0);
+            *total = total.checked_add(*size).ok_or_else(overflow)?;
         }
     }
 
-    sizes
-        .iter()
-        .filter(|(_, &size)| size < 100000)
-        .map(|(_, size)| size)
-        .sum::<u32>()
-        .to_string()
+    Ok(sizes)
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (_input, cmds) = commands(input).unwrap();
-    let mut directories: BTreeMap<String, Vec<File>> = BTreeMap::new();
-    let mut context: Vec<String> = vec![]; 
+pub struct Day7;
 
-    for cmd in cmds.iter() {
-        match cmd {
-            Operation::Cd(Cd::Root) => {
-                context.push("".to_string());
-            },
-            Operation::Cd(Cd::Up) => {
-                context.pop();
-            },
-            Operation::Cd(Cd::Down(name)) => {
-                context.push(name.to_string());
-            },
-            Operation::Ls(files) => {
-                directories
-                    .entry(
-                        context
-                            .iter()
-                            .cloned()
-                            .collect::<Vec<String>>()
-                            .join("/")
-                    )
-                    .or_insert(vec![]);
-                for file in files.iter() {
-                    match file {
-                        Files::File { size, name } => {
-                            directories.entry(
-                                context
-                                    .iter()
-                                    .cloned()
-                                    .collect::<Vec<String>>()
-                                    .join("/")
-                                )
-                                .and_modify(|vec| {
-                                    vec.push(File {
-                                        size: *size,
-                                        name
-                                    })
-                                });
-                        }
-                        Files::Dir(_) => (),
-                    }
-                }
-            },
-        };
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Parsed<'a> = Vec<Operation<'a>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        finish(Self::DAY, input, commands(input))
     }
 
-    let mut sizes: BTreeMap<String, u32> = BTreeMap::new();
-    for (path, files) in directories.iter() {
-        let dirs = path.split("/").collect::<Vec<&str>>();
-        let size = files
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(directory_sizes(parsed)?
             .iter()
-            .map(|File {size, ..} | size)
-            .sum::<u32>();
-        for i in 0..dirs.len() {
-            sizes
-                .entry(
-                    (&dirs[0..=i])
-                        .iter()
-                        .cloned()
-                        .collect::<Vec<&str>>()
-                        .join("/")
-                )
-                .and_modify(|v| *v += size)
-                .or_insert(size);
-        }
+            .filter(|(_, &size)| size < 100000)
+            .map(|(_, size)| size)
+            .sum::<u64>()
+            .to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let sizes = directory_sizes(parsed)?;
+        let used_space: u64 = *sizes
+            .get("")
+            .ok_or_else(|| Error::new(Self::DAY, "the root directory was never listed"))?;
+        let unused_space = 70_000_000u64.checked_sub(used_space).ok_or_else(|| {
+            Error::new(Self::DAY, format!("{used_space} bytes do not fit on the disk"))
+        })?;
+        let need_to_free: u64 = 30_000_000u64.saturating_sub(unused_space);
+        debug!(used_space, need_to_free, "disk usage");
+
+        let mut valid_dirs = sizes
+            .iter()
+            .filter(|(_, &size)| size > need_to_free)
+            .map(|(_, size)| size)
+            .collect::<Vec<&u64>>();
+
+        valid_dirs.sort();
+        Ok(valid_dirs
+            .first()
+            .ok_or_else(|| Error::new(Self::DAY, "no directory frees enough space"))?
+            .to_string())
     }
+}
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day7::part1(&Day7::parse(&normalize(input))?)
+}
 
-    let used_space: u32 = *sizes.get("").unwrap();
-    let need_to_free: u32 = 30_000_000 - (70_000_000 - used_space);
-    dbg!(used_space, need_to_free);
-
-    let mut valid_dirs = sizes
-        .iter()
-        .filter(|(_, &size)| size > need_to_free)
-        .map(|(_, size)| size)
-        .collect::<Vec<&u32>>();
-    
-    valid_dirs.sort();
-    valid_dirs.iter().next().unwrap().to_string()
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day7::part2(&Day7::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
@@ -277,13 +217,27 @@ $ ls
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "95437");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "24933642");
     }
+
+    #[test]
+    fn directories_are_keyed_by_path() {
+        let sizes = directory_sizes(&Day7::parse(INPUT).unwrap()).unwrap();
+        let expected = [("", 48381165), ("/a", 94853), ("/a/e", 584), ("/d", 24933642)];
+        assert_eq!(sizes, expected.map(|(path, size)| (path.to_string(), size)).into());
+    }
+
+    #[test]
+    fn cd_root_goes_back_to_the_root() {
+        let input = "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\n5 f\n$ cd /\n$ cd a\n$ ls\n5 f";
+        let sizes = directory_sizes(&Day7::parse(input).unwrap()).unwrap();
+        assert_eq!(sizes, [("".to_string(), 5), ("/a".to_string(), 5)].into());
+    }
 }
@@ -1,106 +1,65 @@
-fn visible(map: &Vec<Vec<u32>>, i: usize, j: usize) -> bool {
-    if i == 0 || j == 0 || j == map.len() - 1 || i == map.len() - 1 {
-        return true;
-    }
+use aoc_common::{normalize, Direction, Error, Grid, Point, Solution};
 
-    let curr_height = map[i][j];
+fn visible(map: &Grid<u32>, tree: Point) -> bool {
+    let curr_height = map[tree];
 
-    return
-        map[0..i].iter().all(|vec| curr_height > vec[j]) ||
-        map[i+1.. ].iter().all(|vec| curr_height > vec[j]) ||
-        map[i][0..j].iter().all(|height| curr_height > *height) ||
-        map[i][j+1.. ].iter().all(|height| curr_height > *height);
+    Direction::ALL.into_iter().any(|direction| {
+        map.ray(tree, direction).all(|(_, &height)| curr_height > height)
+    })
+}
 
+fn scenic_score(map: &Grid<u32>, tree: Point) -> usize {
+    let treehouse_height = map[tree];
+
+    Direction::ALL.into_iter()
+        .map(|direction| {
+            let mut score = 0;
+            for (_, &height) in map.ray(tree, direction) {
+                score += 1;
+                if height >= treehouse_height {
+                    break;
+                }
+            }
+            score
+        })
+        .product()
 }
 
-fn scenic_score(map: &Vec<Vec<u32>>, i: usize, j: usize) -> usize {
-    let mut scores = vec![0, 0, 0, 0];
-    let treehouse_height: u32 = map[i][j];
-
-    // Up
-    for x in (0..i).rev() {
-        if map[x][j] < treehouse_height {
-            scores[0] += 1;
-        } else if map[x][j] >= treehouse_height {
-            scores[0] += 1;
-            break;
-        }
-    }
-    
-    // Down
-    for x in i+1..map.len() {
-        if map[x][j] < treehouse_height {
-            scores[1] += 1;
-        } else if map[x][j] >= treehouse_height {
-            scores[1] += 1;
-            break;
-        }
-    }
+pub struct Day8;
 
-    // Left
-    for x in (0..j).rev() {
-        if map[i][x] < treehouse_height {
-            scores[2] += 1;
-        } else if map[i][x] >= treehouse_height {
-            scores[2] += 1;
-            break;
-        }
-    }
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Parsed<'a> = Grid<u32>;
 
-    // Right
-    for x in j+1..map.len() {
-        if map[i][x] < treehouse_height {
-            scores[3] += 1;
-        } else if map[i][x] >= treehouse_height {
-            scores[3] += 1;
-            break;
-        }
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        Grid::parse(Self::DAY, input, |c| c.to_digit(10))
     }
 
-    scores.iter().product()
-}
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let total = parsed.points()
+            .filter(|&tree| visible(parsed, tree))
+            .count();
 
-pub fn process_part1(input: &str) -> String {
-    let map: Vec<Vec<u32>> = input
-        .lines()
-        .map(|line| line
-             .chars()
-             .map(|c| c.to_digit(10).unwrap())
-             .collect())
-        .collect();
-
-    let mut total: u32 = 0;
-    for i in 0..map.len() {
-        for j in 0..map.len() {
-            if visible(&map, i, j) {
-                total += 1;
-            }
-        }
+        Ok(total.to_string())
     }
 
-    total.to_string()
-}
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        let highest_score = parsed.points()
+            .map(|tree| scenic_score(parsed, tree))
+            .max()
+            .unwrap_or(0);
 
-pub fn process_part2(input: &str) -> String {
-    let map: Vec<Vec<u32>> = input
-        .lines()
-        .map(|line| line
-             .chars()
-             .map(|c| c.to_digit(10).unwrap())
-             .collect())
-        .collect();
-
-    let mut highest_score = 0;
-    for i in 0..map.len() {
-        for j in 0..map.len() {
-            let score = scenic_score(&map, i, j);
-            if score > highest_score {
-                highest_score = score;
-            }
-        }
+        Ok(highest_score.to_string())
     }
+}
+
+pub fn process_part1(input: &str) -> Result<String, Error> {
+    Day8::part1(&Day8::parse(&normalize(input))?)
+}
 
-    highest_score.to_string()
+pub fn process_part2(input: &str) -> Result<String, Error> {
+    Day8::part2(&Day8::parse(&normalize(input))?)
 }
 
 #[cfg(test)]
@@ -115,13 +74,13 @@ mod tests {
 
     #[test]
     fn part1_works() {
-        let result = process_part1(INPUT);
+        let result = process_part1(INPUT).unwrap();
         assert_eq!(result, "21");
     }
 
     #[test]
     fn part2_works() {
-        let result = process_part2(INPUT);
+        let result = process_part2(INPUT).unwrap();
         assert_eq!(result, "8");
     }
 }
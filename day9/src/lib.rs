@@ -1,15 +1,10 @@
-use std::{str::FromStr, collections::HashSet, vec};
+use std::{str::FromStr, collections::HashSet, io::BufRead};
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down
-}
+use aoc_common::{for_each_line, normalize, Direction, Error, Frame, Grid, Point, Solution, Visualize};
+use tracing::instrument;
 
 #[derive(Clone, Copy, Debug)]
-struct Command {
+pub struct Command {
     direction: Direction,
     count: u32,
 }
@@ -17,133 +12,218 @@ struct Command {
 
 impl FromStr for Command {
 
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split_whitespace().collect();
+        let (direction, count) = s.split_once(' ').ok_or_else(|| {
+            Error::new(Day9::DAY, "expected `<direction> <count>`").at(1, 1)
+        })?;
 
-        let direction = match parts[0] {
+        let direction = match direction {
             "R" => Direction::Right,
             "U" => Direction::Up,
             "L" => Direction::Left,
             "D" => Direction::Down,
-            _ => return Err("Unknown direction".to_string()),
+            _ => {
+                return Err(Error::new(Day9::DAY, format!("unknown direction {direction:?}"))
+                    .at_fragment(s, direction))
+            }
         };
 
-        let count = parts[1].parse::<u32>().expect(&"Invalid counts".to_string());
+        let count = count.parse::<u32>().map_err(|err| {
+            Error::new(Day9::DAY, format!("invalid count {count:?}: {err}"))
+                .at_fragment(s, count)
+        })?;
 
         Ok(Command { direction, count })
     }
 }
 
-fn distance(a: (i32, i32), b: (i32, i32)) -> u32 {
-    ((a.0 - b.0).abs() + (a.1 - b.1).abs()) as u32
-}
+impl Command {
+    /// Which way the head moves.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
 
-fn get_direction(knot1: (i32, i32), knot2: (i32, i32)) -> (i32, i32) {
-    if knot1.0 > knot2.0 && knot1.1 > knot2.1 {
-        (1, 1) // NE
-    } else if knot1.0 > knot2.0 && knot1.1 < knot2.1 {
-        (1, -1) // NW
-    } else if knot1.0 < knot2.0 && knot1.1 > knot2.1 {
-        (-1, 1) // SE
-    } else if knot1.0 < knot2.0 && knot1.1 < knot2.1 {
-        (-1, -1) // SW
-    } else {
-        let mut x_off = knot1.0 - knot2.0;
-        let mut y_off = knot1.1 - knot2.1;
-        if x_off > 0 {
-            x_off = 1;
-        } else if x_off < 0 {
-            x_off = -1;
-        }
-        if y_off > 0 {
-            y_off = 1;
-        } else if y_off < 0 {
-            y_off = -1;
-        }
-        (x_off, y_off)
+    /// How many steps the head takes.
+    pub fn count(&self) -> u32 {
+        self.count
     }
 }
 
-fn move_knot(knot1: (i32, i32), knot2: (i32, i32)) -> (i32, i32) {
-    let dist = distance(knot1, knot2);
-    let is_on_same_line = knot1.0 == knot2.0 || knot1.1 == knot2.1;
-
-    if (is_on_same_line && dist > 1) || 
-        (!is_on_same_line && dist > 2)
-    {
-        let direction = get_direction(knot1, knot2);
-        return (knot2.0 + direction.0, knot2.1 + direction.1);
+/// Drags `knot2` one step towards `knot1` once they stop touching.
+fn move_knot(knot1: Point, knot2: Point) -> Point {
+    if knot1.chebyshev(knot2) > 1 {
+        return knot2 + (knot1 - knot2).signum();
     }
-    knot2 
+    knot2
 }
 
-pub fn part1(input: &str) -> String {
-    let commands: Vec<Command> = input
-        .lines()
-        .map(|s| s.parse::<Command>().unwrap())
-        .collect();
+/// A rope of knots starting at the origin, and every position its tail
+/// has visited.
+struct Rope {
+    knots: Vec<Point>,
+    visited: HashSet<Point>,
+}
+
+impl Rope {
+    fn new(knots: usize) -> Self {
+        Rope {
+            knots: vec![Point::ORIGIN; knots],
+            visited: HashSet::from([Point::ORIGIN]),
+        }
+    }
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut head: (i32, i32) = (0, 0);
-    let mut tail: (i32, i32) = (0, 0);
+    /// Moves the head as `command` says, one step at a time.
+    fn pull(&mut self, Command { direction, count }: Command) {
+        let rope = &mut self.knots;
+        let tail = rope.len() - 1;
+        for _ in 0..count {
+            rope[0] += direction;
 
-    visited.insert(tail);
-    for Command { direction, count } in commands.iter() {
-        for _ in 0..*count {
-            match direction {
-                Direction::Up => head.0 += 1,
-                Direction::Down => head.0 -= 1,
-                Direction::Right => head.1 += 1,
-                Direction::Left => head.1 -= 1,
+            for i in 1..rope.len() {
+                rope[i] = move_knot(rope[i-1], rope[i]);
             }
-            tail = move_knot(head, tail);
-            visited.insert(tail);
+            self.visited.insert(rope[tail]);
         }
     }
+}
+
+/// Counts the positions the last of `knots` knots visits. `observe` sees
+/// the rope and the visited positions after each command.
+#[instrument(level = "debug", skip(commands, observe), fields(commands = commands.len()))]
+fn tail_visits(
+    commands: &[Command],
+    knots: usize,
+    mut observe: impl FnMut(usize, &[Point], &HashSet<Point>),
+) -> usize {
+    let mut rope = Rope::new(knots);
+    for (i, &command) in commands.iter().enumerate() {
+        rope.pull(command);
+        observe(i, &rope.knots, &rope.visited);
+    }
+
+    rope.visited.len()
+}
+
+/// [`tail_visits`] for commands read one line at a time. Memory grows with
+/// the positions the tail visits, not with the length of the input.
+fn stream_tail_visits(input: impl BufRead, knots: usize) -> Result<usize, Error> {
+    let mut rope = Rope::new(knots);
+    for_each_line(Day9::DAY, input, |number, line| {
+        rope.pull(line.parse().map_err(|err: Error| err.on_line(number))?);
+        Ok(())
+    })?;
+    Ok(rope.visited.len())
+}
 
-    visited.len().to_string()
+/// The corners of the area the head covers, which the other knots never
+/// leave either.
+fn bounds(commands: &[Command]) -> (Point, Point) {
+    let (mut min, mut max, mut head) = (Point::ORIGIN, Point::ORIGIN, Point::ORIGIN);
+    for Command { direction, count } in commands {
+        head += *direction * i64::from(*count);
+        min = Point::new(min.x.min(head.x), min.y.min(head.y));
+        max = Point::new(max.x.max(head.x), max.y.max(head.y));
+    }
+    (min, max)
 }
 
+pub struct Day9;
 
-pub fn part2(input: &str) -> String {
+impl Solution for Day9 {
+    const DAY: u8 = 9;
 
-    let commands: Vec<Command> = input
-        .lines()
-        .map(|s| s.parse::<Command>().unwrap())
-        .collect();
+    type Parsed<'a> = Vec<Command>;
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut rope: Vec<(i32, i32)> = vec![];
-    for _ in 0..10 {
-        rope.push((0, 0));
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, s)| s.parse::<Command>().map_err(|err| err.on_line(i + 1)))
+            .collect()
     }
 
-    visited.insert(rope[9]);
-    for Command { direction, count } in commands.iter() {
-        for _ in 0..*count {
-            // Move the head
-            match direction {
-                Direction::Up => rope[0].0 += 1,
-                Direction::Down => rope[0].0 -= 1,
-                Direction::Right => rope[0].1 += 1,
-                Direction::Left => rope[0].1 -= 1,
-            }
-            
-            for i in 1..10 {
-                rope[i] = move_knot(rope[i-1], rope[i]);
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(tail_visits(parsed, 2, |_, _, _| {}).to_string())
+    }
+
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<String, Error> {
+        Ok(tail_visits(parsed, 10, |_, _, _| {}).to_string())
+    }
+}
+
+/// The most columns and rows a frame shows; larger areas are cropped to a
+/// view that follows the head.
+const VIEW: (i64, i64) = (80, 40);
+
+impl Visualize for Day9 {
+    fn frames(
+        parsed: &Self::Parsed<'_>,
+        part: u8,
+        frame: &mut dyn FnMut(Frame),
+    ) -> Result<(), Error> {
+        let knots = if part == 1 { 2 } else { 10 };
+        let (min, max) = bounds(parsed);
+        let too_large = || Error::new(Self::DAY, "the rope moves over too large an area to draw");
+        let width = max.x.checked_sub(min.x).ok_or_else(too_large)?.min(VIEW.0 - 1) + 1;
+        let height = max.y.checked_sub(min.y).ok_or_else(too_large)?.min(VIEW.1 - 1) + 1;
+        tail_visits(parsed, knots, |i, rope, visited| {
+            // The top left corner of the view, centred on the head as far
+            // as the area allows.
+            let corner = Point::new(
+                (rope[0].x - width / 2).clamp(min.x, max.x - width + 1),
+                (rope[0].y - height / 2).clamp(min.y, max.y - height + 1),
+            );
+            let mut cells = Grid::from_fn(width as usize, height as usize, |p| {
+                if visited.contains(&(p + corner)) { '#' } else { '.' }
+            });
+            // Draw the tail first so knots nearer the head stay on top.
+            for (k, &knot) in rope.iter().enumerate().rev() {
+                let Some(cell) = cells.get_mut(knot - corner) else {
+                    continue;
+                };
+                *cell = match k {
+                    0 => 'H',
+                    k if k == knots - 1 => 'T',
+                    k => char::from_digit(k as u32, 10).unwrap_or('*'),
+                };
             }
-            visited.insert(rope[9]);
-        }
+            let Command { direction, count } = parsed[i];
+            let caption = format!(
+                "{direction:?} {count} ({}/{}), tail visited {}",
+                i + 1,
+                parsed.len(),
+                visited.len()
+            );
+            frame(Frame { caption, cells });
+        });
+        Ok(())
     }
+}
+
+pub fn part1(input: &str) -> Result<String, Error> {
+    Day9::part1(&Day9::parse(&normalize(input))?)
+}
+
+pub fn part2(input: &str) -> Result<String, Error> {
+    Day9::part2(&Day9::parse(&normalize(input))?)
+}
+
+/// [`part1`] for an input read one line at a time.
+pub fn stream_part1(input: impl BufRead) -> Result<String, Error> {
+    Ok(stream_tail_visits(input, 2)?.to_string())
+}
 
-    visited.len().to_string()
+/// [`part2`] for an input read one line at a time.
+pub fn stream_part2(input: impl BufRead) -> Result<String, Error> {
+    Ok(stream_tail_visits(input, 10)?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     
     const INPUT_PART1: &str = "R 4
 U 4
@@ -165,13 +245,81 @@ U 20";
 
     #[test]
     fn part1_works() {
-        let result = part1(INPUT_PART1);
+        let result = part1(INPUT_PART1).unwrap();
         assert_eq!(result, "13");
     }
 
     #[test]
     fn part2_works() {
-        let result = part2(INPUT_PART2);
+        let result = part2(INPUT_PART2).unwrap();
         assert_eq!(result, "36");
     }
+
+    #[test]
+    fn invalid_count_is_located() {
+        let err = part1("R 4\nU four").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 9, line 2, column 3: invalid count \"four\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn streaming_matches_the_whole_input() {
+        for input in [INPUT_PART1, INPUT_PART2, "", "R 4\r\nU 4\r\n\r\n", "R 4\nU four"] {
+            assert_eq!(stream_part1(input.as_bytes()), part1(input), "{input:?}");
+            assert_eq!(stream_part2(input.as_bytes()), part2(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn frames_cover_the_head_path() {
+        let commands = Day9::parse(INPUT_PART1).unwrap();
+        let mut frames = vec![];
+        Day9::frames(&commands, 1, &mut |frame| frames.push(frame)).unwrap();
+        assert_eq!(frames.len(), 8);
+        let last = frames.last().unwrap();
+        assert_eq!((last.cells.width(), last.cells.height()), (6, 5));
+        assert_eq!(last.caption, "Right 2 (8/8), tail visited 13");
+        assert_eq!(last.cells.to_string(), "..##..\n...##.\n.TH##.\n....#.\n####..\n");
+    }
+
+    #[test]
+    fn large_areas_are_cropped_around_the_head() {
+        let commands = Day9::parse("R 100000\nU 50000\nL 3").unwrap();
+        let mut frames = vec![];
+        Day9::frames(&commands, 2, &mut |frame| frames.push(frame)).unwrap();
+        for frame in &frames {
+            assert_eq!((frame.cells.width(), frame.cells.height()), (80, 40));
+            assert!(frame.cells.to_string().contains('H'));
+        }
+        assert!(frames[2].cells.to_string().contains('T'));
+    }
+
+    fn commands() -> impl Strategy<Value = String> {
+        let direction = prop::sample::select(vec!["R", "U", "L", "D"]);
+        prop::collection::vec((direction, 1u32..20), 1..100).prop_map(|commands| {
+            commands
+                .iter()
+                .map(|(direction, count)| format!("{direction} {count}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn tail_visits_at_most_one_square_per_head_step(input in commands()) {
+            let steps: u32 = Day9::parse(&input).unwrap().iter().map(|c| c.count).sum();
+            for visited in [part1(&input), part2(&input)] {
+                prop_assert!(visited.unwrap().parse::<u32>().unwrap() <= steps + 1);
+            }
+        }
+
+        #[test]
+        fn streaming_gives_the_same_answers(input in commands()) {
+            prop_assert_eq!(stream_part1(input.as_bytes()), part1(&input));
+            prop_assert_eq!(stream_part2(input.as_bytes()), part2(&input));
+        }
+    }
 }
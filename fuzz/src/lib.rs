@@ -0,0 +1,25 @@
+//! Shared bodies of the fuzz targets: hostile input may only ever produce
+//! an `Error`, never a panic, an arithmetic overflow or a stack overflow.
+//!
+//! Run a target with `cargo +nightly fuzz run solve_day13` from the
+//! repository root; seeding `fuzz/corpus/<target>/` with the day's
+//! `input.txt` gets to the interesting paths much sooner.
+
+use aoc_common::Solution;
+
+/// Feeds `data` to the day's parser.
+pub fn parse<S: Solution>(data: &[u8]) {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = S::parse(input);
+    }
+}
+
+/// Feeds `data` to the day's parser and, when it is accepted, to both parts.
+pub fn solve<S: Solution>(data: &[u8]) {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(parsed) = S::parse(input) {
+            let _ = S::part1(&parsed);
+            let _ = S::part2(&parsed);
+        }
+    }
+}